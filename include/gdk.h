@@ -29,6 +29,7 @@ extern "C" {
 #define GA_SESSION_LOST (-3)
 #define GA_TIMEOUT (-4)
 #define GA_NOT_AUTHORIZED (-5)
+#define GA_CANCELLED (-6)
 
 /** Logging levels */
 #define GA_NONE 0
@@ -103,6 +104,16 @@ GDK_API int GA_disconnect(struct GA_session* session);
  */
 GDK_API int GA_reconnect_hint(struct GA_session* session, const GA_json* hint);
 
+/**
+ * Cancel any requests to the server that are currently in progress.
+ *
+ * Calls waiting on a server response, including multi-page fetches such as
+ * listing transactions, return GA_CANCELLED. The session remains connected.
+ *
+ * :param session: The session to use.
+ */
+GDK_API int GA_cancel_pending_requests(struct GA_session* session);
+
 /**
  * Get the current SOCKS5 url for the embedded Tor daemon, if any.
  *
@@ -140,6 +151,34 @@ GDK_API int GA_http_get(struct GA_session* session, const GA_json* params, GA_js
  */
 GDK_API int GA_refresh_assets(struct GA_session* session, const GA_json* params, GA_json** output);
 
+/**
+ * Get the registry information for a single asset.
+ *
+ * :param session: The session to use.
+ * :param params: the :ref:`asset-lookup-params` giving the asset to look up.
+ * :param output: Destination for the asset JSON, in the same format as the
+ *|     entries returned by `GA_refresh_assets`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ *
+ * Assets not in the locally cached registry are fetched individually from
+ * the registry and cached, so that assets seen in transactions since the
+ * registry was last refreshed can be displayed.
+ */
+GDK_API int GA_lookup_asset(struct GA_session* session, const GA_json* params, GA_json** output);
+
+/**
+ * Import the blinding factors of outputs the wallet cannot unblind itself.
+ *
+ * :param session: The session to use.
+ * :param details: The :ref:`import-blinders` giving the blinding factors to import.
+ *
+ * Outputs paying the wallet but blinded to a key other than the wallet's own
+ * (for example from swaps or peg-ins) are unblinded using the imported data,
+ * once it has been checked against the output commitments. Call
+ * `GA_rescan_transactions` to update previously fetched transactions.
+ */
+GDK_API int GA_import_blinders(struct GA_session* session, const GA_json* details);
+
 /**
  * Validate asset domain name.
  * (This is a interface stub)
@@ -168,10 +207,45 @@ GDK_API int GA_register_user(
  * :param password: The user's password to decrypt a 27 word mnemonic, or a blank string if none.
  * :param call: Destination for the resulting GA_auth_handler to perform the login.
  *|     Returned GA_auth_handler should be freed using `GA_destroy_auth_handler`.
+ *
+ * When logging in with a hardware wallet, the subaccount xpubs fetched from the
+ * device are stored locally, and later logins skip requesting them again unless
+ * subaccounts have been created elsewhere in the meantime.
  */
 GDK_API int GA_login(struct GA_session* session, const GA_json* hw_device, const char* mnemonic, const char* password,
     struct GA_auth_handler** call);
 
+/**
+ * Provide the key used to encrypt the wallet's locally stored data.
+ *
+ * :param session: The session to use.
+ * :param details: The :ref:`cache-encryption-key` to use.
+ *
+ * By default the key is derived from the wallet's keys at login. Hosts that can
+ * hold keys in secure hardware (e.g. Android Keystore or the Secure Enclave) may
+ * instead unwrap a key of their own and provide it here. Must be called before
+ * logging in, and the same key must be provided at every login to access the
+ * previously stored data. The key and any password are discarded when the
+ * session is destroyed.
+ *
+ * A user password or PIN may also be given, which is mixed into the key so that
+ * the wallet's keys alone are not enough to decrypt the stored data. Logging in
+ * fails with "Invalid cache password" if it doesn't match the password in use.
+ */
+GDK_API int GA_set_cache_encryption_key(struct GA_session* session, const GA_json* details);
+
+/**
+ * Change the user password mixed into the key encrypting locally stored data.
+ *
+ * :param session: The session to use.
+ * :param details: The :ref:`cache-password` to use.
+ *
+ * The stored data is re-encrypted under the new key and the old copy removed.
+ * If re-encrypting fails the old password remains in use. Must be called after
+ * logging in.
+ */
+GDK_API int GA_change_cache_password(struct GA_session* session, const GA_json* details);
+
 /**
  * Authenticate a user.
  *
@@ -265,6 +339,21 @@ GDK_API int GA_get_subaccount(struct GA_session* session, uint32_t subaccount, s
  */
 GDK_API int GA_rename_subaccount(struct GA_session* session, uint32_t subaccount, const char* new_name);
 
+/**
+ * Hide or unhide a subaccount.
+ *
+ * :param session: The session to use.
+ * :param subaccount: The value of "pointer" from :ref:`subaccount-list` or
+ *|                   :ref:`subaccount-detail` for the subaccount to hide.
+ * :param hidden: Non-zero to hide the subaccount, zero to unhide it.
+ *
+ * Hidden subaccounts are still returned by `GA_get_subaccounts`, with
+ * ``"hidden"`` set to ``true``, so that UIs can archive subaccounts that are no
+ * longer used. The flag is stored in the local cache and is not shared with
+ * other devices. The main subaccount cannot be hidden.
+ */
+GDK_API int GA_set_subaccount_hidden(struct GA_session* session, uint32_t subaccount, uint32_t hidden);
+
 /**
  * Get a page of the user's transaction history.
  *
@@ -287,6 +376,17 @@ GDK_API int GA_get_transactions(struct GA_session* session, const GA_json* detai
  */
 GDK_API int GA_get_receive_address(struct GA_session* session, const GA_json* details, struct GA_auth_handler** call);
 
+/**
+ * Get a new address to receive coins to, along with everything needed to display it.
+ *
+ * :param session: The session to use.
+ * :param details: :ref:`receive-payload-details`.
+ * :param call: Destination for the resulting GA_auth_handler to complete the action.
+ *|     The call result is :ref:`receive-payload`.
+ *|     Returned GA_auth_handler should be freed using `GA_destroy_auth_handler`.
+ */
+GDK_API int GA_get_receive_payload(struct GA_session* session, const GA_json* details, struct GA_auth_handler** call);
+
 /**
  * Get the user's unspent transaction outputs.
  *
@@ -312,6 +412,45 @@ GDK_API int GA_get_unspent_outputs(struct GA_session* session, const GA_json* de
 GDK_API int GA_get_unspent_outputs_for_private_key(
     struct GA_session* session, const char* private_key, const char* password, uint32_t unused, GA_json** utxos);
 
+/**
+ * Freeze an unspent transaction output, excluding it from default coin selection.
+ *
+ * :param session: The session to use.
+ * :param details: The :ref:`utxo-outpoint` of the output to freeze.
+ *
+ * Frozen outputs are stored locally and are only spent when explicitly
+ * selected with the ``"manual"`` utxo strategy.
+ */
+GDK_API int GA_freeze_utxo(struct GA_session* session, const GA_json* details);
+
+/**
+ * Unfreeze a previously frozen unspent transaction output.
+ *
+ * :param session: The session to use.
+ * :param details: The :ref:`utxo-outpoint` of the output to unfreeze.
+ */
+GDK_API int GA_unfreeze_utxo(struct GA_session* session, const GA_json* details);
+
+/**
+ * Get the policy for unspent outputs that should not be spent by default.
+ *
+ * :param session: The session to use.
+ * :param output: Destination for the :ref:`utxo-policy`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ */
+GDK_API int GA_get_utxo_policy(struct GA_session* session, GA_json** output);
+
+/**
+ * Set the policy for unspent outputs that should not be spent by default.
+ *
+ * :param session: The session to use.
+ * :param details: The :ref:`utxo-policy` to set.
+ *
+ * Outputs matching the policy, such as those sent in dust attacks, are returned
+ * separately by `GA_get_unspent_outputs` and are not spent unless requested.
+ */
+GDK_API int GA_set_utxo_policy(struct GA_session* session, const GA_json* details);
+
 /**
  * Get a transaction's details.
  *
@@ -322,6 +461,33 @@ GDK_API int GA_get_unspent_outputs_for_private_key(
  */
 GDK_API int GA_get_transaction_details(struct GA_session* session, const char* txhash_hex, GA_json** transaction);
 
+/**
+ * Get the unblinded values, assets and blinding factors of a Liquid transaction's outputs.
+ *
+ * :param session: The session to use.
+ * :param txhash_hex: The transaction hash of the transaction to get the blinders of.
+ * :param output: Destination for the :ref:`tx-blinders`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ *
+ * Blinders are known for outputs paying the wallet, and for all blinded outputs
+ * of transactions sent from this device. The result also contains an explorer
+ * URL that reveals the known outputs, for sharing with third parties.
+ */
+GDK_API int GA_get_transaction_blinders(struct GA_session* session, const char* txhash_hex, GA_json** output);
+
+/**
+ * Decode a signed or unsigned transaction for review.
+ *
+ * :param session: The session to use.
+ * :param details: The :ref:`decode-tx-details` giving the transaction to decode.
+ * :param output: Destination for the decoded :ref:`tx-detail`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ *
+ * This allows transactions produced outside of this library to be checked before
+ * they are signed. The transaction is not sent anywhere.
+ */
+GDK_API int GA_decode_transaction(struct GA_session* session, const GA_json* details, GA_json** output);
+
 /**
  * The sum of unspent outputs destined to user's wallet.
  *
@@ -332,6 +498,91 @@ GDK_API int GA_get_transaction_details(struct GA_session* session, const char* t
  */
 GDK_API int GA_get_balance(struct GA_session* session, const GA_json* details, struct GA_auth_handler** call);
 
+/**
+ * Get the balance of a subaccount as a list of per-asset entries.
+ *
+ * :param session: The session to use.
+ * :param details: :ref:`balance-details` giving the subaccount details to get the balance for.
+ * :param call: Destination for the resulting GA_auth_handler to complete the action.
+ *|     The call result is :ref:`balance-detailed`.
+ *|     Returned GA_auth_handler should be freed using `GA_destroy_auth_handler`.
+ */
+GDK_API int GA_get_balance_detailed(
+    struct GA_session* session, const GA_json* details, struct GA_auth_handler** call);
+
+/**
+ * Get the balance history of a subaccount as a time series.
+ *
+ * :param session: The session to use.
+ * :param details: :ref:`balance-history-details` giving the subaccount and period to get the history for.
+ * :param call: Destination for the resulting GA_auth_handler to complete the action.
+ *|     The call result is :ref:`balance-history`.
+ *|     Returned GA_auth_handler should be freed using `GA_destroy_auth_handler`.
+ */
+GDK_API int GA_get_balance_history(
+    struct GA_session* session, const GA_json* details, struct GA_auth_handler** call);
+
+/**
+ * Get the monthly incoming, outgoing and fee totals of a subaccount.
+ *
+ * :param session: The session to use.
+ * :param details: :ref:`spending-summary-details` giving the subaccount and months to summarize.
+ * :param call: Destination for the resulting GA_auth_handler to complete the action.
+ *|     The call result is :ref:`spending-summary`.
+ *|     Returned GA_auth_handler should be freed using `GA_destroy_auth_handler`.
+ */
+GDK_API int GA_get_spending_summary(
+    struct GA_session* session, const GA_json* details, struct GA_auth_handler** call);
+
+/**
+ * Get the addresses the wallet has handed out for a subaccount.
+ *
+ * :param session: The session to use.
+ * :param details: :ref:`generated-addresses-details` giving the subaccount to get addresses for.
+ * :param call: Destination for the resulting GA_auth_handler to complete the action.
+ *|     The call result is :ref:`generated-addresses`.
+ *|     Returned GA_auth_handler should be freed using `GA_destroy_auth_handler`.
+ */
+GDK_API int GA_get_generated_addresses(
+    struct GA_session* session, const GA_json* details, struct GA_auth_handler** call);
+
+/**
+ * Discard locally cached transaction data and fetch it again from the server.
+ *
+ * :param session: The session to use.
+ * :param details: :ref:`rescan-details` giving the subaccount to rescan, if not all.
+ * :param call: Destination for the resulting GA_auth_handler to complete the action.
+ *|     The call result is :ref:`rescan-result`.
+ *|     Returned GA_auth_handler should be freed using `GA_destroy_auth_handler`.
+ *
+ * Data kept only on this device, such as address labels and which addresses
+ * have been used, is not affected.
+ * A ``"rescan"`` notification is sent as each subaccount completes.
+ */
+GDK_API int GA_rescan_transactions(
+    struct GA_session* session, const GA_json* details, struct GA_auth_handler** call);
+
+/**
+ * Get the progress of fetching and unblinding the wallet's transaction history.
+ *
+ * :param session: The session to use.
+ * :param output: Destination for the returned :ref:`sync-status`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ */
+GDK_API int GA_get_sync_status(struct GA_session* session, GA_json** output);
+
+/**
+ * Export the unblinded values of a Liquid subaccount's outputs, encrypted to a third party.
+ *
+ * :param session: The session to use.
+ * :param details: :ref:`unblinded-outputs-export-details` giving the outputs to export.
+ * :param call: Destination for the resulting GA_auth_handler to complete the action.
+ *|     The call result is :ref:`unblinded-outputs-export`.
+ *|     Returned GA_auth_handler should be freed using `GA_destroy_auth_handler`.
+ */
+GDK_API int GA_export_unblinded_outputs(
+    struct GA_session* session, const GA_json* details, struct GA_auth_handler** call);
+
 /**
  * The list of allowed currencies for all available pricing sources.
  *
@@ -351,6 +602,37 @@ GDK_API int GA_get_available_currencies(struct GA_session* session, GA_json** cu
  */
 GDK_API int GA_convert_amount(struct GA_session* session, const GA_json* value_details, GA_json** output);
 
+/**
+ * Parse a user entered amount string and convert it to all denominations.
+ *
+ * :param session: The session to use.
+ * :param amount_details: :ref:`parse-amount` giving the amount string to parse.
+ * :param output: Destination for the converted values :ref:`balance-data`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ */
+GDK_API int GA_parse_amount(struct GA_session* session, const GA_json* amount_details, GA_json** output);
+
+/**
+ * Parse a BIP 21 payment URI or plain address for the sessions network.
+ *
+ * :param session: The session to use.
+ * :param details: :ref:`parse-uri` giving the URI to parse.
+ * :param output: Destination for the parsed payment details :ref:`parsed-uri`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ */
+GDK_API int GA_parse_uri(struct GA_session* session, const GA_json* details, GA_json** output);
+
+/**
+ * Parse and validate an address for the sessions network.
+ *
+ * :param session: The session to use.
+ * :param details: :ref:`parse-address` giving the address to parse.
+ * :param output: Destination for the :ref:`parsed-address`, which explains why
+ *|     the address can't be paid to if it is invalid.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ */
+GDK_API int GA_parse_address(struct GA_session* session, const GA_json* details, GA_json** output);
+
 /**
  * Set a PIN for the user wallet.
  *
@@ -392,6 +674,16 @@ GDK_API int GA_create_transaction(
 GDK_API int GA_sign_transaction(
     struct GA_session* session, const GA_json* transaction_details, struct GA_auth_handler** call);
 
+/**
+ * Check whether the user's inputs to a transaction can be signed, without signing them.
+ *
+ * :param session: The session to use.
+ * :param transaction_details: The :ref:`sign-tx-details` to check, previously returned from GA_create_transaction.
+ * :param output: Destination for the resulting :ref:`can-sign-result`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ */
+GDK_API int GA_can_sign_transaction(struct GA_session* session, const GA_json* transaction_details, GA_json** output);
+
 /**
  * Broadcast a non-Green signed transaction to the P2P network.
  *
@@ -482,6 +774,121 @@ GDK_API int GA_set_transaction_memo(
  */
 GDK_API int GA_get_fee_estimates(struct GA_session* session, GA_json** estimates);
 
+/**
+ * Estimate the size and fee of a transaction without selecting any coins.
+ *
+ * :param session: The session to use.
+ * :param details: The :ref:`estimate-fee-details` describing the transaction.
+ * :param output: Destination for the returned :ref:`estimate-fee`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ */
+GDK_API int GA_estimate_transaction_fee(struct GA_session* session, const GA_json* details, GA_json** output);
+
+/**
+ * Get the options for speeding up an unconfirmed transaction by RBF or CPFP.
+ *
+ * :param session: The session to use.
+ * :param details: The :ref:`fee-bump-details` giving the transaction to speed up.
+ * :param output: Destination for the returned :ref:`fee-bump-options`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ */
+GDK_API int GA_get_fee_bump_options(struct GA_session* session, const GA_json* details, GA_json** output);
+
+/**
+ * Get the parameters of the network the session is connected to.
+ *
+ * :param session: The session to use.
+ * :param output: Destination for the returned :ref:`network-details`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ *
+ * The returned JSON contains the :ref:`network` configuration along with
+ * the fee and dust policy values in use by the session.
+ */
+GDK_API int GA_get_network_parameters(struct GA_session* session, GA_json** output);
+
+/**
+ * Get the journal of security relevant events recorded for the wallet.
+ *
+ * :param session: The session to use.
+ * :param output: Destination for the returned :ref:`event-journal`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ *
+ * Events are appended as they occur and are stored encrypted in the wallet's
+ * local cache. The journal is best-effort: it is lost along with the cache,
+ * e.g. if the cache file is deleted or can't be decrypted.
+ */
+GDK_API int GA_get_event_journal(struct GA_session* session, GA_json** output);
+
+/**
+ * Get the size of the wallet's local cache, broken down by the data it holds.
+ *
+ * :param session: The session to use.
+ * :param output: Destination for the returned :ref:`cache-stats`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ */
+GDK_API int GA_get_cache_stats(struct GA_session* session, GA_json** output);
+
+/**
+ * Get the addresses and scripts the user has denied payments to.
+ *
+ * :param session: The session to use.
+ * :param output: Destination for the returned :ref:`denylist`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ */
+GDK_API int GA_get_denylist(struct GA_session* session, GA_json** output);
+
+/**
+ * Set the addresses and scripts the user has denied payments to.
+ *
+ * :param session: The session to use.
+ * :param denylist: The :ref:`denylist` to set, replacing any existing list.
+ *
+ * Transactions paying a denied address or script fail to be created, and
+ * transactions sending funds from one are flagged in the transaction list.
+ */
+GDK_API int GA_set_denylist(struct GA_session* session, const GA_json* denylist);
+
+/**
+ * Get the labels the user has given to addresses in a subaccount.
+ *
+ * :param session: The session to use.
+ * :param details: :ref:`address-labels-details` giving the subaccount to get labels for.
+ * :param output: Destination for the returned :ref:`address-labels`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ */
+GDK_API int GA_get_address_labels(struct GA_session* session, const GA_json* details, GA_json** output);
+
+/**
+ * Set or remove the label of an address.
+ *
+ * :param session: The session to use.
+ * :param details: :ref:`address-label` giving the address and its label.
+ *
+ * Labels are stored encrypted in the local cache and are not shared with other
+ * devices. They are returned with transaction inputs/outputs and unspent outputs.
+ */
+GDK_API int GA_set_address_label(struct GA_session* session, const GA_json* details);
+
+/**
+ * Get the user's saved transaction templates.
+ *
+ * :param session: The session to use.
+ * :param output: Destination for the returned :ref:`tx-templates`.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ */
+GDK_API int GA_get_transaction_templates(struct GA_session* session, GA_json** output);
+
+/**
+ * Save or remove a named transaction template.
+ *
+ * :param session: The session to use.
+ * :param details: :ref:`tx-template` giving the template name and its contents.
+ *
+ * A saved template can be passed by name as ``"template"`` in the
+ * :ref:`transaction-details` given to `GA_create_transaction`.
+ */
+GDK_API int GA_set_transaction_template(struct GA_session* session, const GA_json* details);
+
 /**
  * Get the user's mnemonic passphrase.
  *
@@ -518,6 +925,27 @@ GDK_API int GA_get_system_message(struct GA_session* session, char** message_tex
  */
 GDK_API int GA_ack_system_message(struct GA_session* session, const char* message_text, struct GA_auth_handler** call);
 
+/**
+ * Sign a message with the user's key for a wallet address.
+ *
+ * :param session: The session to use.
+ * :param details: The :ref:`sign-message-details` giving the address and message to sign.
+ * :param call: Destination for the resulting GA_auth_handler to sign the message.
+ *|     The call result is :ref:`message-signature`.
+ *|     Returned GA_auth_handler should be freed using `GA_destroy_auth_handler`.
+ */
+GDK_API int GA_sign_message(struct GA_session* session, const GA_json* details, struct GA_auth_handler** call);
+
+/**
+ * Verify a message signature for an address.
+ *
+ * :param session: The session to use.
+ * :param details: The :ref:`message-signature` to verify. Only its "address", "message"
+ *|     and "signature" are required.
+ * :param valid: Destination for the result: GA_TRUE if the signature is valid else GA_FALSE
+ */
+GDK_API int GA_verify_message(struct GA_session* session, const GA_json* details, uint32_t* valid);
+
 /**
  * Get the two factor configuration for the current user.
  *
@@ -736,6 +1164,14 @@ GDK_API int GA_get_random_bytes(size_t num_bytes, unsigned char* output_bytes, s
  */
 GDK_API int GA_generate_mnemonic(char** output);
 
+/**
+ * Generate a new random 12 word BIP 39 mnemonic.
+ *
+ * :param output: The generated mnemonic phrase.
+ *|     Returned string should be freed using `GA_destroy_string`.
+ */
+GDK_API int GA_generate_mnemonic_12(char** output);
+
 /**
  * Validate a BIP 39 mnemonic.
  *
@@ -744,6 +1180,15 @@ GDK_API int GA_generate_mnemonic(char** output);
  */
 GDK_API int GA_validate_mnemonic(const char* mnemonic, uint32_t* valid);
 
+/**
+ * Check a BIP 39 mnemonic, explaining why it is invalid if it is not.
+ *
+ * :param mnemonic: The mnemonic phrase
+ * :param output: Destination for the :ref:`mnemonic-check` result.
+ *|     Returned GA_json should be freed using `GA_destroy_json`.
+ */
+GDK_API int GA_check_mnemonic(const char* mnemonic, GA_json** output);
+
 /**
  * Register a network configuration
  *
@@ -1,6 +1,8 @@
+#include <algorithm>
 #include <cctype>
 #include <cstring>
 #include <iostream>
+#include <map>
 #include <stdexcept>
 
 #include "boost_wrapper.hpp"
@@ -29,6 +31,52 @@ namespace sdk {
         static const std::vector<std::string> NON_SATOSHI_KEYS{ "btc", "mbtc", "ubtc", "bits", "sats", "fiat",
             "fiat_currency", "fiat_rate" };
 
+        // Recognised unit suffixes for user entered amounts
+        static const std::map<std::string, std::string> UNIT_SUFFIXES{ { "btc", "btc" }, { "lbtc", "btc" },
+            { "mbtc", "mbtc" }, { "ubtc", "ubtc" }, { "\u00b5btc", "ubtc" }, { "bit", "bits" }, { "bits", "bits" },
+            { "sat", "sats" }, { "sats", "sats" }, { "satoshi", "sats" }, { "satoshis", "sats" } };
+        // Maximum number of decimal places for each unit
+        static const std::map<std::string, size_t> UNIT_DECIMALS{ { "btc", 8 }, { "mbtc", 5 }, { "ubtc", 2 },
+            { "bits", 2 }, { "sats", 0 }, { "fiat", 2 } };
+
+        static bool is_digit(char c) { return std::isdigit(static_cast<unsigned char>(c)) != 0; }
+
+        static bool is_group_separator(char c, char decimal_separator)
+        {
+            return c == ' ' || c == '\'' || ((c == '.' || c == ',') && c != decimal_separator);
+        }
+
+        // Determine the decimal separator used by an amount string when the caller hasn't specified one
+        static char detect_decimal_separator(const std::string& number, size_t max_decimals)
+        {
+            const auto dot_pos = number.rfind('.');
+            const auto comma_pos = number.rfind(',');
+            if (dot_pos == std::string::npos && comma_pos == std::string::npos) {
+                return '.'; // No fractional part
+            }
+            if (dot_pos != std::string::npos && comma_pos != std::string::npos) {
+                // Both present: the last one separates the fractional part
+                return dot_pos > comma_pos ? '.' : ',';
+            }
+            const char c = dot_pos != std::string::npos ? '.' : ',';
+            const auto pos = std::min(dot_pos, comma_pos);
+            const char other = c == '.' ? ',' : '.';
+            if (std::count(number.begin(), number.end(), c) > 1) {
+                // Repeated, so must be separating thousands
+                return other;
+            }
+            const std::string integer_part = number.substr(0, pos);
+            if (number.size() - pos - 1 == 3 && !integer_part.empty() && integer_part != "0") {
+                if (max_decimals < 3) {
+                    // Too many digits to be a fraction in this unit
+                    return other;
+                }
+                // e.g. "1,234 BTC": could be either a fraction or a thousands separator
+                throw user_error(res::id_invalid_amount);
+            }
+            return c;
+        }
+
         template <typename T> static std::string fmt(const T& fiat, size_t dp = 2)
         {
             return fiat_type(fiat).str(dp, std::ios_base::fixed | std::ios_base::showpoint);
@@ -148,5 +196,81 @@ namespace sdk {
         return fmt(conversion_type(value_str), dp);
     }
 
+    nlohmann::json amount::parse(
+        const std::string& amount_str, const std::string& default_unit, const std::string& decimal_separator)
+    {
+        GDK_RUNTIME_ASSERT_MSG(decimal_separator.empty() || decimal_separator == "." || decimal_separator == ",",
+            "unsupported decimal separator");
+        GDK_RUNTIME_ASSERT_MSG(UNIT_DECIMALS.count(default_unit) != 0, "unknown unit");
+
+        // Split the string into its number and optional unit suffix
+        const std::string str = boost::algorithm::trim_copy(amount_str);
+        const auto last_digit = std::find_if(str.rbegin(), str.rend(), is_digit);
+        if (last_digit == str.rend()) {
+            throw user_error(res::id_invalid_amount);
+        }
+        const size_t unit_pos = str.rend() - last_digit;
+        const std::string number = str.substr(0, unit_pos);
+        const std::string suffix = boost::algorithm::to_lower_copy(boost::algorithm::trim_copy(str.substr(unit_pos)));
+
+        std::string unit = default_unit;
+        if (!suffix.empty()) {
+            const auto p = UNIT_SUFFIXES.find(suffix);
+            if (p == UNIT_SUFFIXES.end()) {
+                throw user_error(res::id_invalid_amount);
+            }
+            unit = p->second;
+        }
+
+        const size_t max_decimals = UNIT_DECIMALS.at(unit);
+        const char dp
+            = decimal_separator.empty() ? detect_decimal_separator(number, max_decimals) : decimal_separator.front();
+
+        // Collect the integer digits, validating any thousands grouping
+        std::string integer_digits;
+        std::vector<size_t> groups{ 0 };
+        size_t i = 0;
+        for (; i < number.size() && number[i] != dp; ++i) {
+            const char c = number[i];
+            if (is_digit(c)) {
+                integer_digits.push_back(c);
+                ++groups.back();
+            } else if (is_group_separator(c, dp) && groups.back() != 0) {
+                groups.push_back(0);
+            } else {
+                throw user_error(res::id_invalid_amount);
+            }
+        }
+        if (groups.size() > 1) {
+            const bool valid_groups = groups.front() <= 3
+                && std::all_of(groups.begin() + 1, groups.end(), [](size_t n) { return n == 3; });
+            if (!valid_groups) {
+                throw user_error(res::id_invalid_amount);
+            }
+        }
+
+        // Collect the fractional digits, if any
+        std::string fraction_digits;
+        if (i != number.size()) {
+            fraction_digits = number.substr(i + 1);
+            if (!std::all_of(fraction_digits.begin(), fraction_digits.end(), is_digit)) {
+                throw user_error(res::id_invalid_amount);
+            }
+        }
+        if (integer_digits.empty() && fraction_digits.empty()) {
+            throw user_error(res::id_invalid_amount);
+        }
+        if (fraction_digits.size() > max_decimals) {
+            // More precision than the unit can represent
+            throw user_error(res::id_invalid_amount);
+        }
+
+        std::string normalized = integer_digits.empty() ? "0" : integer_digits;
+        if (!fraction_digits.empty()) {
+            normalized += "." + fraction_digits;
+        }
+        return { { unit, normalized } };
+    }
+
 } // namespace sdk
 } // namespace ga
@@ -47,6 +47,11 @@ namespace sdk {
         // Format a number string to include 'dp' decimal places
         static std::string format_amount(const std::string& value_str, size_t dp);
 
+        // Parse a user entered amount string into an amount JSON suitable for convert().
+        // An empty decimal_separator means the separator is detected from the input
+        static nlohmann::json parse(
+            const std::string& amount_str, const std::string& default_unit, const std::string& decimal_separator);
+
         amount& operator=(value_type v)
         {
             m_value = v;
@@ -9,6 +9,22 @@ namespace sdk {
         {
             return boost::algorithm::starts_with(msg, "Missing prevout:");
         }
+
+        // Rejections from the node's mempool when broadcasting a transaction
+        static bool is_fee_too_low(const std::string& msg)
+        {
+            return boost::algorithm::contains(msg, "min relay fee not met")
+                || boost::algorithm::contains(msg, "mempool min fee not met")
+                || boost::algorithm::contains(msg, "insufficient fee");
+        }
+
+        // "missing-inputs" isn't included, as it is also returned when the
+        // node hasn't seen a parent transaction yet
+        static bool is_conflicting(const std::string& msg)
+        {
+            return boost::algorithm::contains(msg, "txn-mempool-conflict")
+                || boost::algorithm::contains(msg, "bad-txns-inputs-missingorspent");
+        }
     } // namespace
 
     std::pair<std::string, std::string> get_error_details(const autobahn::call_error& e)
@@ -37,6 +53,11 @@ namespace sdk {
             return std::make_pair(details.first, res::id_transaction_already_confirmed);
         } else if (details.second == "Invalid PGP key") {
             return std::make_pair(details.first, res::id_invalid_pgp_key);
+        } else if (is_fee_too_low(details.second)) {
+            return std::make_pair(details.first, res::id_fee_rate_is_below_minimum);
+        } else if (is_conflicting(details.second)) {
+            // The inputs are spent by another transaction, in the mempool or a block
+            return std::make_pair(details.first, res::id_double_spend);
         }
         return details;
     }
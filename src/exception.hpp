@@ -38,6 +38,14 @@ namespace sdk {
         }
     };
 
+    class cancelled_error : public std::runtime_error {
+    public:
+        cancelled_error()
+            : std::runtime_error("request cancelled")
+        {
+        }
+    };
+
     class assertion_error : public std::runtime_error {
     public:
         explicit assertion_error(const std::string& what)
@@ -43,6 +43,8 @@ template <typename F, typename... Args> static auto c_invoke(F&& f, Args&&... ar
         return GA_RECONNECT;
     } catch (const ga::sdk::timeout_error& e) {
         return GA_TIMEOUT;
+    } catch (const ga::sdk::cancelled_error& e) {
+        return GA_CANCELLED;
     } catch (const std::exception& e) {
         return GA_ERROR;
     }
@@ -183,6 +185,9 @@ GDK_DEFINE_C_FUNCTION_1(GA_disconnect, struct GA_session*, session, { session->d
 GDK_DEFINE_C_FUNCTION_2(GA_reconnect_hint, struct GA_session*, session, const GA_json*, hint,
     { session->reconnect_hint(*json_cast(hint)); });
 
+GDK_DEFINE_C_FUNCTION_1(
+    GA_cancel_pending_requests, struct GA_session*, session, { session->cancel_pending_requests(); })
+
 GDK_DEFINE_C_FUNCTION_1(GA_check_proxy_connectivity, const GA_json*, params,
     { ga::sdk::session::check_proxy_connectivity(*json_cast(params)); });
 
@@ -195,6 +200,12 @@ GDK_DEFINE_C_FUNCTION_3(GA_http_get, struct GA_session*, session, const GA_json*
 GDK_DEFINE_C_FUNCTION_3(GA_refresh_assets, struct GA_session*, session, const GA_json*, params, GA_json**, output,
     { *json_cast(output) = new nlohmann::json(session->refresh_assets(*json_cast(params))); });
 
+GDK_DEFINE_C_FUNCTION_3(GA_lookup_asset, struct GA_session*, session, const GA_json*, params, GA_json**, output,
+    { *json_cast(output) = new nlohmann::json(session->lookup_asset(*json_cast(params))); });
+
+GDK_DEFINE_C_FUNCTION_2(GA_import_blinders, struct GA_session*, session, const GA_json*, details,
+    { session->import_blinders(*json_cast(details)); })
+
 GDK_DEFINE_C_FUNCTION_3(GA_validate_asset_domain_name, struct GA_session*, session, const GA_json*, params, GA_json**,
     output, { *json_cast(output) = new nlohmann::json(session->validate_asset_domain_name(*json_cast((params)))); });
 
@@ -202,6 +213,12 @@ GDK_DEFINE_C_FUNCTION_4(GA_register_user, struct GA_session*, session, const GA_
     struct GA_auth_handler**, call,
     { *call = auth_cast(new ga::sdk::register_call(*session, *json_cast(hw_device), mnemonic)); })
 
+GDK_DEFINE_C_FUNCTION_2(GA_set_cache_encryption_key, struct GA_session*, session, const GA_json*, details,
+    { session->set_cache_encryption_key(*json_cast(details)); })
+
+GDK_DEFINE_C_FUNCTION_2(GA_change_cache_password, struct GA_session*, session, const GA_json*, details,
+    { session->change_cache_password(*json_cast(details)); })
+
 GDK_DEFINE_C_FUNCTION_3(GA_login_with_pin, struct GA_session*, session, const char*, pin, const GA_json*, pin_data,
     { session->login_with_pin(pin, *json_cast(pin_data)); })
 
@@ -218,6 +235,39 @@ GDK_DEFINE_C_FUNCTION_3(GA_set_watch_only, struct GA_session*, session, const ch
 GDK_DEFINE_C_FUNCTION_2(GA_get_watch_only_username, struct GA_session*, session, char**, username,
     { *username = to_c_string(session->get_watch_only_username()); })
 
+GDK_DEFINE_C_FUNCTION_3(GA_estimate_transaction_fee, struct GA_session*, session, const GA_json*, details, GA_json**,
+    output, { *json_cast(output) = new nlohmann::json(session->estimate_transaction_fee(*json_cast(details))); })
+
+GDK_DEFINE_C_FUNCTION_3(GA_get_fee_bump_options, struct GA_session*, session, const GA_json*, details, GA_json**,
+    output, { *json_cast(output) = new nlohmann::json(session->get_fee_bump_options(*json_cast(details))); })
+
+GDK_DEFINE_C_FUNCTION_2(GA_get_network_parameters, struct GA_session*, session, GA_json**, output,
+    { *json_cast(output) = new nlohmann::json(session->get_network_details()); })
+
+GDK_DEFINE_C_FUNCTION_2(GA_get_event_journal, struct GA_session*, session, GA_json**, output,
+    { *json_cast(output) = new nlohmann::json(session->get_event_journal()); })
+
+GDK_DEFINE_C_FUNCTION_2(GA_get_cache_stats, struct GA_session*, session, GA_json**, output,
+    { *json_cast(output) = new nlohmann::json(session->get_cache_stats()); })
+
+GDK_DEFINE_C_FUNCTION_2(GA_get_denylist, struct GA_session*, session, GA_json**, output,
+    { *json_cast(output) = new nlohmann::json(session->get_denylist()); })
+
+GDK_DEFINE_C_FUNCTION_2(GA_set_denylist, struct GA_session*, session, const GA_json*, denylist,
+    { session->set_denylist(*json_cast(denylist)); })
+
+GDK_DEFINE_C_FUNCTION_3(GA_get_address_labels, struct GA_session*, session, const GA_json*, details, GA_json**,
+    output, { *json_cast(output) = new nlohmann::json(session->get_address_labels(*json_cast(details))); })
+
+GDK_DEFINE_C_FUNCTION_2(GA_set_address_label, struct GA_session*, session, const GA_json*, details,
+    { session->set_address_label(*json_cast(details)); })
+
+GDK_DEFINE_C_FUNCTION_2(GA_get_transaction_templates, struct GA_session*, session, GA_json**, output,
+    { *json_cast(output) = new nlohmann::json(session->get_transaction_templates()); })
+
+GDK_DEFINE_C_FUNCTION_2(GA_set_transaction_template, struct GA_session*, session, const GA_json*, details,
+    { session->set_transaction_template(*json_cast(details)); })
+
 GDK_DEFINE_C_FUNCTION_2(GA_get_fee_estimates, struct GA_session*, session, GA_json**, estimates,
     { *json_cast(estimates) = new nlohmann::json(session->get_fee_estimates()); })
 
@@ -231,6 +281,13 @@ GDK_DEFINE_C_FUNCTION_3(GA_ack_system_message, struct GA_session*, session, cons
     struct GA_auth_handler**, call,
     { *call = auth_cast(new ga::sdk::ack_system_message_call(*session, message_text)); });
 
+GDK_DEFINE_C_FUNCTION_3(GA_sign_message, struct GA_session*, session, const GA_json*, details,
+    struct GA_auth_handler**, call,
+    { *call = auth_cast(new ga::sdk::sign_message_call(*session, *json_cast(details))); });
+
+GDK_DEFINE_C_FUNCTION_3(GA_verify_message, struct GA_session*, session, const GA_json*, details, uint32_t*,
+    valid, { *valid = session->verify_message(*json_cast(details)) ? GA_TRUE : GA_FALSE; });
+
 GDK_DEFINE_C_FUNCTION_2(GA_get_twofactor_config, struct GA_session*, session, GA_json**, config,
     { *json_cast(config) = new nlohmann::json(session->get_twofactor_config()); })
 
@@ -242,6 +299,10 @@ GDK_DEFINE_C_FUNCTION_3(GA_sign_transaction, struct GA_session*, session, const
     struct GA_auth_handler**, call,
     { *call = auth_cast(new ga::sdk::sign_transaction_call(*session, *json_cast(transaction_details))); });
 
+GDK_DEFINE_C_FUNCTION_3(GA_can_sign_transaction, struct GA_session*, session, const GA_json*, transaction_details,
+    GA_json**, output,
+    { *json_cast(output) = new nlohmann::json(session->can_sign_transaction(*json_cast(transaction_details))); })
+
 GDK_DEFINE_C_FUNCTION_1(GA_send_nlocktimes, struct GA_session*, session, { session->send_nlocktimes(); })
 
 GDK_DEFINE_C_FUNCTION_3(GA_get_expired_deposits, struct GA_session*, session, const GA_json*, deposit_details,
@@ -285,6 +346,9 @@ GDK_DEFINE_C_FUNCTION_3(GA_get_subaccount, struct GA_session*, session, uint32_t
 GDK_DEFINE_C_FUNCTION_3(GA_rename_subaccount, struct GA_session*, session, uint32_t, subaccount, const char*, new_name,
     { session->rename_subaccount(subaccount, new_name); })
 
+GDK_DEFINE_C_FUNCTION_3(GA_set_subaccount_hidden, struct GA_session*, session, uint32_t, subaccount, uint32_t, hidden,
+    { session->set_subaccount_hidden(subaccount, hidden != 0); })
+
 GDK_DEFINE_C_FUNCTION_3(GA_get_transactions, struct GA_session*, session, const GA_json*, details,
     struct GA_auth_handler**, call,
     { *call = auth_cast(new ga::sdk::get_transactions_call(*session, *json_cast(details))); });
@@ -293,9 +357,40 @@ GDK_DEFINE_C_FUNCTION_3(GA_get_receive_address, struct GA_session*, session, con
     struct GA_auth_handler**, call,
     { *call = auth_cast(new ga::sdk::get_receive_address_call(*session, *json_cast(details))); });
 
+GDK_DEFINE_C_FUNCTION_3(GA_get_receive_payload, struct GA_session*, session, const GA_json*, details,
+    struct GA_auth_handler**, call,
+    { *call = auth_cast(new ga::sdk::get_receive_payload_call(*session, *json_cast(details))); });
+
 GDK_DEFINE_C_FUNCTION_3(GA_get_balance, struct GA_session*, session, const GA_json*, details, struct GA_auth_handler**,
     call, { *call = auth_cast(new ga::sdk::get_balance_call(*session, *json_cast(details))); });
 
+GDK_DEFINE_C_FUNCTION_3(GA_get_balance_detailed, struct GA_session*, session, const GA_json*, details,
+    struct GA_auth_handler**, call,
+    { *call = auth_cast(new ga::sdk::get_balance_detailed_call(*session, *json_cast(details))); });
+
+GDK_DEFINE_C_FUNCTION_3(GA_get_balance_history, struct GA_session*, session, const GA_json*, details,
+    struct GA_auth_handler**, call,
+    { *call = auth_cast(new ga::sdk::get_balance_history_call(*session, *json_cast(details))); });
+
+GDK_DEFINE_C_FUNCTION_3(GA_get_spending_summary, struct GA_session*, session, const GA_json*, details,
+    struct GA_auth_handler**, call,
+    { *call = auth_cast(new ga::sdk::get_spending_summary_call(*session, *json_cast(details))); });
+
+GDK_DEFINE_C_FUNCTION_3(GA_get_generated_addresses, struct GA_session*, session, const GA_json*, details,
+    struct GA_auth_handler**, call,
+    { *call = auth_cast(new ga::sdk::get_generated_addresses_call(*session, *json_cast(details))); });
+
+GDK_DEFINE_C_FUNCTION_3(GA_rescan_transactions, struct GA_session*, session, const GA_json*, details,
+    struct GA_auth_handler**, call,
+    { *call = auth_cast(new ga::sdk::rescan_transactions_call(*session, *json_cast(details))); });
+
+GDK_DEFINE_C_FUNCTION_2(GA_get_sync_status, struct GA_session*, session, GA_json**, output,
+    { *json_cast(output) = new nlohmann::json(session->get_sync_status()); })
+
+GDK_DEFINE_C_FUNCTION_3(GA_export_unblinded_outputs, struct GA_session*, session, const GA_json*, details,
+    struct GA_auth_handler**, call,
+    { *call = auth_cast(new ga::sdk::export_unblinded_outputs_call(*session, *json_cast(details))); });
+
 GDK_DEFINE_C_FUNCTION_3(GA_get_unspent_outputs, struct GA_session*, session, const GA_json*, details,
     struct GA_auth_handler**, call,
     { *call = auth_cast(new ga::sdk::get_unspent_outputs_call(*session, *json_cast(details))); });
@@ -306,15 +401,42 @@ GDK_DEFINE_C_FUNCTION_5(GA_get_unspent_outputs_for_private_key, struct GA_sessio
             = new nlohmann::json(session->get_unspent_outputs_for_private_key(private_key, password, unused));
     })
 
+GDK_DEFINE_C_FUNCTION_2(GA_freeze_utxo, struct GA_session*, session, const GA_json*, details,
+    { session->freeze_utxo(*json_cast(details)); })
+
+GDK_DEFINE_C_FUNCTION_2(GA_unfreeze_utxo, struct GA_session*, session, const GA_json*, details,
+    { session->unfreeze_utxo(*json_cast(details)); })
+
+GDK_DEFINE_C_FUNCTION_2(GA_get_utxo_policy, struct GA_session*, session, GA_json**, output,
+    { *json_cast(output) = new nlohmann::json(session->get_utxo_policy()); })
+
+GDK_DEFINE_C_FUNCTION_2(GA_set_utxo_policy, struct GA_session*, session, const GA_json*, details,
+    { session->set_utxo_policy(*json_cast(details)); })
+
 GDK_DEFINE_C_FUNCTION_3(GA_get_transaction_details, struct GA_session*, session, const char*, txhash_hex, GA_json**,
     transaction, { *json_cast(transaction) = new nlohmann::json(session->get_transaction_details(txhash_hex)); })
 
+GDK_DEFINE_C_FUNCTION_3(GA_get_transaction_blinders, struct GA_session*, session, const char*, txhash_hex, GA_json**,
+    output, { *json_cast(output) = new nlohmann::json(session->get_transaction_blinders(txhash_hex)); })
+
+GDK_DEFINE_C_FUNCTION_3(GA_decode_transaction, struct GA_session*, session, const GA_json*, details, GA_json**, output,
+    { *json_cast(output) = new nlohmann::json(session->decode_transaction(*json_cast(details))); })
+
 GDK_DEFINE_C_FUNCTION_2(GA_get_available_currencies, struct GA_session*, session, GA_json**, currencies,
     { *json_cast(currencies) = new nlohmann::json(session->get_available_currencies()); })
 
 GDK_DEFINE_C_FUNCTION_3(GA_convert_amount, struct GA_session*, session, const GA_json*, value_details, GA_json**,
     output, { *json_cast(output) = new nlohmann::json(session->convert_amount(*json_cast(value_details))); })
 
+GDK_DEFINE_C_FUNCTION_3(GA_parse_amount, struct GA_session*, session, const GA_json*, amount_details, GA_json**,
+    output, { *json_cast(output) = new nlohmann::json(session->parse_amount(*json_cast(amount_details))); })
+
+GDK_DEFINE_C_FUNCTION_3(GA_parse_uri, struct GA_session*, session, const GA_json*, details, GA_json**, output,
+    { *json_cast(output) = new nlohmann::json(session->parse_uri(*json_cast(details))); })
+
+GDK_DEFINE_C_FUNCTION_3(GA_parse_address, struct GA_session*, session, const GA_json*, details, GA_json**, output,
+    { *json_cast(output) = new nlohmann::json(session->parse_address(*json_cast(details))); })
+
 GDK_DEFINE_C_FUNCTION_5(GA_set_pin, struct GA_session*, session, const char*, mnemonic, const char*, pin, const char*,
     device_id, GA_json**, pin_data,
     { *json_cast(pin_data) = new nlohmann::json(session->set_pin(mnemonic, pin, device_id)); })
@@ -333,6 +455,9 @@ GDK_DEFINE_C_FUNCTION_2(GA_register_network, const char*, name, const GA_json*,
 GDK_DEFINE_C_FUNCTION_1(GA_get_networks, GA_json**, output,
     { *json_cast(output) = new nlohmann::json(ga::sdk::network_parameters::get_all()); });
 
+GDK_DEFINE_C_FUNCTION_2(GA_check_mnemonic, const char*, mnemonic, GA_json**, output,
+    { *json_cast(output) = new nlohmann::json(ga::sdk::check_mnemonic(mnemonic)); });
+
 GDK_DEFINE_C_FUNCTION_2(GA_get_uniform_uint32_t, uint32_t, upper_bound, uint32_t*, output,
     { *output = ga::sdk::get_uniform_uint32_t(upper_bound); });
 
@@ -10,6 +10,7 @@
 #include "logging.hpp"
 #include "signer.hpp"
 #include "transaction_utils.hpp"
+#include "utils.hpp"
 #include "xpub_hdkey.hpp"
 
 namespace ga {
@@ -73,6 +74,9 @@ namespace sdk {
     {
         if (m_action == "get_xpubs" || m_action == "sign_message" || m_action == "sign_tx"
             || m_action == "get_receive_address" || m_action == "create_transaction" || m_action == "get_balance"
+            || m_action == "get_balance_detailed" || m_action == "get_balance_history"
+            || m_action == "get_spending_summary" || m_action == "get_generated_addresses"
+            || m_action == "rescan_transactions" || m_action == "export_unblinded_outputs"
             || m_action == "get_subaccounts" || m_action == "get_subaccount" || m_action == "get_transactions"
             || m_action == "get_unspent_outputs" || m_action == "get_expired_deposits") {
             // Hardware action, so provide the caller with the device information
@@ -324,7 +328,8 @@ namespace sdk {
 
                 if (m_challenge.empty()) {
                     // Compute the challenge with the master pubkey
-                    const auto master_xpub = get_xpub(xpubs.at(0));
+                    m_master_xpub = xpubs.at(0);
+                    const auto master_xpub = get_xpub(m_master_xpub);
                     const auto btc_version = m_session.get_network_parameters().btc_version();
                     m_challenge = m_session.get_challenge(address_from_xpub(btc_version, master_xpub));
 
@@ -345,14 +350,18 @@ namespace sdk {
                 // Log in and set up the session
                 m_session.authenticate(args.at("signature"), "GA", std::string(), m_hw_device);
 
-                // Ask the caller for the xpubs for each subaccount
-                std::vector<nlohmann::json> paths;
-                for (const auto sa : m_session.get_subaccounts()) {
-                    paths.emplace_back(ga_user_pubkeys::get_subaccount_path(sa["pointer"]));
+                if (!m_session.resume_from_snapshot(m_master_xpub)) {
+                    // Ask the caller for the xpubs for each subaccount
+                    std::vector<nlohmann::json> paths;
+                    for (const auto sa : m_session.get_subaccounts()) {
+                        paths.emplace_back(ga_user_pubkeys::get_subaccount_path(sa["pointer"]));
+                    }
+                    set_data("get_xpubs");
+                    m_twofactor_data["paths"] = paths;
+                    return state_type::resolve_code;
                 }
-                set_data("get_xpubs");
-                m_twofactor_data["paths"] = paths;
-                return state_type::resolve_code;
+                // Resumed using the xpubs from our last login;
+                // fall through to the required_ca check down there...
             }
         }
 
@@ -497,6 +506,44 @@ namespace sdk {
         return state_type::done;
     }
 
+    //
+    // Sign message
+    //
+    sign_message_call::sign_message_call(session& session, const nlohmann::json& details)
+        : auth_handler(session, "sign_message")
+        , m_details(details)
+    {
+        if (m_state == state_type::error) {
+            return;
+        }
+
+        if (m_hw_device.empty()) {
+            m_state = state_type::make_call;
+        } else {
+            try {
+                m_result = m_session.get_message_signing_details(details);
+                m_state = state_type::resolve_code;
+                set_data("sign_message");
+                m_twofactor_data["message"] = m_result["message"];
+                m_twofactor_data["path"] = m_result["path"];
+            } catch (const std::exception& e) {
+                set_error(e.what());
+            }
+        }
+    }
+
+    auth_handler::state_type sign_message_call::call_impl()
+    {
+        if (m_hw_device.empty()) {
+            m_result = m_session.sign_message(m_details);
+        } else {
+            const nlohmann::json args = nlohmann::json::parse(m_code);
+            m_details["hw_signature"] = args.at("signature");
+            m_result = m_session.sign_message(m_details);
+        }
+        return state_type::done;
+    }
+
     //
     // Sign tx
     //
@@ -597,7 +644,7 @@ namespace sdk {
 
                 size_t i = 0;
                 for (const auto& out : outputs) {
-                    if (!out.at("is_fee")) {
+                    if (!out.at("is_fee") && !json_get_value(out, "is_unconfidential", false)) {
                         m_session.blind_output(m_twofactor_data["transaction"], tx, i, out, asset_commitments[i],
                             value_commitments[i], abfs[i], vbfs[i]);
                     }
@@ -661,12 +708,52 @@ namespace sdk {
             }
 
             // Blind the address
+            m_result["unconfidential_address"] = m_result["address"];
+            m_result["blinding_key"] = pub_blinding_key;
             m_result["address"] = m_session.blind_address(m_result["address"], pub_blinding_key);
         }
 
+        if (m_details.contains("pointer")) {
+            // Previously generated addresses are usually re-shown as a QR code
+            const auto& net_params = m_session.get_network_parameters();
+            const nlohmann::json params = nlohmann::json::object();
+            m_result["bip21_uri"] = make_bitcoin_uri(net_params.bip21_prefix(), m_result["address"], params);
+        }
         return state_type::done;
     }
 
+    //
+    // Get receive payload
+    //
+    get_receive_payload_call::get_receive_payload_call(session& session, const nlohmann::json& details)
+        : get_receive_address_call(session, details)
+    {
+    }
+
+    auth_handler::state_type get_receive_payload_call::call_impl()
+    {
+        const auto state = get_receive_address_call::call_impl();
+
+        const auto& net_params = m_session.get_network_parameters();
+        nlohmann::json params = nlohmann::json::object();
+        const auto satoshi_p = m_details.find("satoshi");
+        if (satoshi_p != m_details.end()) {
+            // Amounts are given in the consensus form, i.e. 8 decimal places for all assets
+            params["amount"] = amount::convert({ { "satoshi", *satoshi_p } }, std::string(), std::string())["btc"];
+        }
+        if (m_session.is_liquid()) {
+            std::string asset_tag = m_details.value("asset_tag", std::string{});
+            if (asset_tag.empty() || asset_tag == "btc") {
+                asset_tag = net_params.policy_asset();
+            }
+            if (satoshi_p != m_details.end() || asset_tag != net_params.policy_asset()) {
+                params["assetid"] = asset_tag;
+            }
+        }
+        m_result["bip21_uri"] = make_bitcoin_uri(net_params.bip21_prefix(), m_result["address"], params);
+        return state;
+    }
+
     //
     // Create transaction
     //
@@ -815,6 +902,90 @@ namespace sdk {
         return state_type::done;
     }
 
+    //
+    // Get detailed balance
+    //
+    get_balance_detailed_call::get_balance_detailed_call(session& session, const nlohmann::json& details)
+        : needs_unblind_call("get_balance_detailed", session, details)
+    {
+    }
+
+    auth_handler::state_type get_balance_detailed_call::wrapped_call_impl()
+    {
+        m_result = m_session.get_balance_detailed(m_details);
+        return state_type::done;
+    }
+
+    //
+    // Get balance history
+    //
+    get_balance_history_call::get_balance_history_call(session& session, const nlohmann::json& details)
+        : needs_unblind_call("get_balance_history", session, details)
+    {
+    }
+
+    auth_handler::state_type get_balance_history_call::wrapped_call_impl()
+    {
+        m_result = m_session.get_balance_history(m_details);
+        return state_type::done;
+    }
+
+    //
+    // Get spending summary
+    //
+    get_spending_summary_call::get_spending_summary_call(session& session, const nlohmann::json& details)
+        : needs_unblind_call("get_spending_summary", session, details)
+    {
+    }
+
+    auth_handler::state_type get_spending_summary_call::wrapped_call_impl()
+    {
+        m_result = m_session.get_spending_summary(m_details);
+        return state_type::done;
+    }
+
+    //
+    // Get generated addresses
+    //
+    get_generated_addresses_call::get_generated_addresses_call(session& session, const nlohmann::json& details)
+        : needs_unblind_call("get_generated_addresses", session, details)
+    {
+    }
+
+    auth_handler::state_type get_generated_addresses_call::wrapped_call_impl()
+    {
+        m_result = m_session.get_generated_addresses(m_details);
+        return state_type::done;
+    }
+
+    //
+    // Rescan transactions
+    //
+    rescan_transactions_call::rescan_transactions_call(session& session, const nlohmann::json& details)
+        : needs_unblind_call("rescan_transactions", session, details)
+    {
+    }
+
+    auth_handler::state_type rescan_transactions_call::wrapped_call_impl()
+    {
+        m_result = m_session.rescan_transactions(m_details);
+        return state_type::done;
+    }
+
+    //
+    // Export unblinded outputs
+    //
+    export_unblinded_outputs_call::export_unblinded_outputs_call(session& session, const nlohmann::json& details)
+        : needs_unblind_call("export_unblinded_outputs", session, details)
+    {
+    }
+
+    auth_handler::state_type export_unblinded_outputs_call::wrapped_call_impl()
+    {
+        m_result = m_session.export_unblinded_outputs(m_details);
+        return state_type::done;
+    }
+
     //
     // Get subaccounts
     //
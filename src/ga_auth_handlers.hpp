@@ -25,6 +25,7 @@ namespace sdk {
         state_type call_impl() override;
 
         std::string m_challenge;
+        std::string m_master_xpub;
         std::string m_mnemonic;
         std::string m_password;
 
@@ -59,6 +60,16 @@ namespace sdk {
         std::pair<std::string, std::vector<uint32_t>> m_message_info;
     };
 
+    class sign_message_call : public auth_handler {
+    public:
+        sign_message_call(session& session, const nlohmann::json& details);
+
+    private:
+        state_type call_impl() override;
+
+        nlohmann::json m_details;
+    };
+
     class sign_transaction_call : public auth_handler {
     public:
         sign_transaction_call(session& session, const nlohmann::json& tx_details);
@@ -73,12 +84,20 @@ namespace sdk {
     public:
         get_receive_address_call(session& session, const nlohmann::json& details);
 
-    private:
+    protected:
         state_type call_impl() override;
 
         const nlohmann::json m_details;
     };
 
+    class get_receive_payload_call : public get_receive_address_call {
+    public:
+        get_receive_payload_call(session& session, const nlohmann::json& details);
+
+    private:
+        state_type call_impl() override;
+    };
+
     class create_transaction_call : public auth_handler {
     public:
         create_transaction_call(session& session, const nlohmann::json& details);
@@ -111,6 +130,54 @@ namespace sdk {
         state_type wrapped_call_impl() override;
     };
 
+    class get_balance_detailed_call : public needs_unblind_call {
+    public:
+        get_balance_detailed_call(session& session, const nlohmann::json& details);
+
+    private:
+        state_type wrapped_call_impl() override;
+    };
+
+    class get_balance_history_call : public needs_unblind_call {
+    public:
+        get_balance_history_call(session& session, const nlohmann::json& details);
+
+    private:
+        state_type wrapped_call_impl() override;
+    };
+
+    class get_spending_summary_call : public needs_unblind_call {
+    public:
+        get_spending_summary_call(session& session, const nlohmann::json& details);
+
+    private:
+        state_type wrapped_call_impl() override;
+    };
+
+    class get_generated_addresses_call : public needs_unblind_call {
+    public:
+        get_generated_addresses_call(session& session, const nlohmann::json& details);
+
+    private:
+        state_type wrapped_call_impl() override;
+    };
+
+    class rescan_transactions_call : public needs_unblind_call {
+    public:
+        rescan_transactions_call(session& session, const nlohmann::json& details);
+
+    private:
+        state_type wrapped_call_impl() override;
+    };
+
+    class export_unblinded_outputs_call : public needs_unblind_call {
+    public:
+        export_unblinded_outputs_call(session& session, const nlohmann::json& details);
+
+    private:
+        state_type wrapped_call_impl() override;
+    };
+
     class get_subaccounts_call : public needs_unblind_call {
     public:
         get_subaccounts_call(session& session);
@@ -1,7 +1,11 @@
 #include <algorithm>
 #include <array>
+#include <cstdio>
+#include <fcntl.h>
 #include <fstream>
+#include <iterator>
 #include <openssl/evp.h>
+#include <unistd.h>
 #include <vector>
 
 #include "assertion.hpp"
@@ -26,6 +30,10 @@ namespace sdk {
         constexpr int AES_BUFFER = 4096;
         constexpr int OPENSSL_SUCCESS = 1;
         constexpr int VERSION = 0;
+        // Databases larger than this are compacted when loaded
+        constexpr sqlite3_int64 MAX_DB_SIZE = 16 * 1024 * 1024;
+        // KeyValue entries with these key prefixes can be recreated from the server
+        const std::vector<std::string> REFETCHABLE_PREFIXES = { "rawtx" };
 
         static std::unique_ptr<sqlite3> get_new_memory_db()
         {
@@ -35,25 +43,40 @@ namespace sdk {
             return std::unique_ptr<sqlite3>(tmpdb);
         }
 
-        static auto get_db()
+        // Create any missing tables. Tables added since a db file was written are
+        // created when it is loaded, so new tables never require a version change
+        static void create_tables(sqlite3* db)
         {
-            auto db = get_new_memory_db();
-            const auto exec_check = [&db](const char* sql) {
+            const auto exec_check = [db](const char* sql) {
                 char* err_msg = nullptr;
-                const int rc = sqlite3_exec(db.get(), sql, 0, 0, &err_msg);
+                const int rc = sqlite3_exec(db, sql, 0, 0, &err_msg);
                 if (rc != SQLITE_OK) {
                     GDK_LOG_SEV(log_level::info) << "Bad exec_check RC " << rc << " err_msg: " << err_msg;
                     GDK_RUNTIME_ASSERT(false);
                 }
             };
-            exec_check("CREATE TABLE LiquidOutput(txid BLOB NOT NULL, vout INTEGER NOT NULL, assetid BLOB NOT NULL, "
-                       "satoshi INTEGER NOT NULL, abf BLOB NOT NULL, vbf BLOB NOT NULL, PRIMARY KEY (txid, vout));");
+            exec_check("CREATE TABLE IF NOT EXISTS LiquidOutput(txid BLOB NOT NULL, vout INTEGER NOT NULL, assetid "
+                       "BLOB NOT NULL, satoshi INTEGER NOT NULL, abf BLOB NOT NULL, vbf BLOB NOT NULL, "
+                       "PRIMARY KEY (txid, vout));");
+
+            exec_check(
+                "CREATE TABLE IF NOT EXISTS KeyValue(key BLOB NOT NULL, value BLOB NOT NULL, PRIMARY KEY(key));");
 
-            exec_check("CREATE TABLE KeyValue(key BLOB NOT NULL, value BLOB NOT NULL, PRIMARY KEY(key));");
+            exec_check("CREATE TABLE IF NOT EXISTS LiquidBlindingNonce(pubkey BLOB NOT NULL, script BLOB NOT NULL, "
+                       "nonce BLOB NOT NULL, PRIMARY KEY(pubkey, script));");
 
-            exec_check("CREATE TABLE LiquidBlindingNonce(pubkey BLOB NOT NULL, script BLOB NOT NULL, nonce BLOB NOT "
-                       "NULL, PRIMARY KEY(pubkey, script));");
+            // Append only: events are never updated or deleted, but are lost with the cache
+            exec_check(
+                "CREATE TABLE IF NOT EXISTS EventJournal(id INTEGER PRIMARY KEY AUTOINCREMENT, event BLOB NOT NULL);");
 
+            exec_check("CREATE TABLE IF NOT EXISTS GeneratedAddress(address BLOB NOT NULL, subaccount INTEGER NOT "
+                       "NULL, details BLOB NOT NULL, PRIMARY KEY(address));");
+        }
+
+        static auto get_db()
+        {
+            auto db = get_new_memory_db();
+            create_tables(db.get());
             return db;
         }
 
@@ -73,12 +96,12 @@ namespace sdk {
             GDK_RUNTIME_ASSERT_MSG(rc2 == SQLITE_OK, sqlite3_errmsg(sqlite3_db_handle(stmt)));
         }
 
-        static void openssl_encrypt(byte_span_t key, byte_span_t data, const std::string& path)
+        static bool openssl_encrypt(byte_span_t key, byte_span_t data, const std::string& path)
         {
             GDK_RUNTIME_ASSERT(!key.empty() && !data.empty());
             std::ofstream f(path, f.out | f.binary);
             if (!f.is_open()) {
-                return;
+                return false;
             }
             std::array<unsigned char, AES_BUFFER> buff;
             get_random_bytes(AES_GCM_IV_SIZE, buff.data(), AES_GCM_IV_SIZE);
@@ -115,6 +138,20 @@ namespace sdk {
             rc = EVP_CIPHER_CTX_ctrl(ctx.get(), EVP_CTRL_GCM_GET_TAG, AES_GCM_TAG_SIZE, buff.data());
             GDK_RUNTIME_ASSERT(rc == OPENSSL_SUCCESS);
             write_all(AES_GCM_TAG_SIZE);
+            f.close();
+            return !f.fail();
+        }
+
+        // Flush a written file to disk, so that it is complete before it is renamed over
+        static bool sync_file(const std::string& path)
+        {
+            const int fd = open(path.c_str(), O_RDONLY);
+            if (fd == -1) {
+                return false;
+            }
+            const bool synced = fsync(fd) == 0;
+            close(fd);
+            return synced;
         }
 
         static boost::optional<std::vector<unsigned char>> openssl_decrypt(byte_span_t key, const std::string& path)
@@ -181,6 +218,24 @@ namespace sdk {
             return datadir + "/" + std::to_string(version) + unique_db + ".sqliteaesgcm";
         }
 
+        // The key check file records a value derived from the encryption key in use for
+        // the cache that base_key (the key without any cache password) would open
+        static boost::optional<std::string> get_key_check_file(
+            byte_span_t base_key, const std::string& network, const uint32_t type)
+        {
+            const auto path = get_persistent_storage_file(base_key, network, type);
+            if (!path) {
+                return boost::none;
+            }
+            return path.get() + ".keycheck";
+        }
+
+        static std::array<unsigned char, HMAC_SHA256_LEN> get_key_check(byte_span_t encryption_key)
+        {
+            static const std::string tag("gdk cache key check");
+            return hmac_sha256(encryption_key, ustring_span(tag));
+        }
+
         static void clean_up_old_db(byte_span_t encryption_key, const uint32_t type, const std::string& network)
         {
             const auto exist = [](const auto& path) {
@@ -193,6 +248,9 @@ namespace sdk {
                     GDK_LOG_SEV(log_level::info) << "Deleting old version " << version << " db file " << path.get();
                     unlink(path->c_str());
                 }
+                if (path) {
+                    unlink((path.get() + ".bak").c_str());
+                }
             }
         }
 
@@ -217,6 +275,24 @@ namespace sdk {
             bind_blob(stmt, 2, script);
         }
 
+        static sqlite3_int64 get_db_size(sqlite3* db)
+        {
+            const auto pragma_value = [db](const char* sql) {
+                const auto stmt = std::unique_ptr<sqlite3_stmt>(get_stmt(db, sql));
+                GDK_RUNTIME_ASSERT(sqlite3_step(stmt.get()) == SQLITE_ROW);
+                return sqlite3_column_int64(stmt.get(), 0);
+            };
+            return pragma_value("PRAGMA page_count;") * pragma_value("PRAGMA page_size;");
+        }
+
+        // Return the prefix of a KeyValue key, i.e. the key without any txhash,
+        // subaccount or other suffix identifying a particular entry
+        static std::string get_key_prefix(const std::string& key)
+        {
+            const auto p = std::find_if(key.begin(), key.end(), [](char c) { return c < 'a' || c > 'z'; });
+            return std::string(key.begin(), p);
+        }
+
         static bool has_result(sqlite3_stmt* stmt, int rc)
         {
             if (rc == SQLITE_DONE) {
@@ -247,6 +323,12 @@ namespace sdk {
               m_db.get(), "INSERT INTO KeyValue(key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value=?2;"))
         , m_stmt_keyvalue_search(get_stmt(m_db.get(), "SELECT value FROM KeyValue WHERE key = ?1;"))
         , m_stmt_keyvalue_delete(get_stmt(m_db.get(), "DELETE FROM KeyValue WHERE key = ?1;"))
+        , m_stmt_event_insert(get_stmt(m_db.get(), "INSERT INTO EventJournal (event) VALUES (?1);"))
+        , m_stmt_event_search(get_stmt(m_db.get(), "SELECT id, event FROM EventJournal ORDER BY id;"))
+        , m_stmt_generated_address_insert(get_stmt(m_db.get(),
+              "INSERT OR IGNORE INTO GeneratedAddress (address, subaccount, details) VALUES (?1, ?2, ?3);"))
+        , m_stmt_generated_address_search(get_stmt(
+              m_db.get(), "SELECT details FROM GeneratedAddress WHERE subaccount = ?1 ORDER BY rowid;"))
     {
     }
 
@@ -269,7 +351,18 @@ namespace sdk {
         }
         const auto key = sha256(encryption_key);
         const auto data = gsl::make_span(reinterpret_cast<const unsigned char*>(db), db_size);
-        openssl_encrypt(key, data, path.get());
+
+        // Write to a temporary file and rename it into place, so that a crash while
+        // writing can't leave a truncated file. The previous version is kept as a
+        // backup to load from in case the new file is lost or corrupted anyway
+        const std::string tmp_path = path.get() + ".tmp";
+        if (!openssl_encrypt(key, data, tmp_path) || !sync_file(tmp_path)) {
+            GDK_LOG_SEV(log_level::info) << "Failed to write db file " << tmp_path;
+            unlink(tmp_path.c_str());
+            return;
+        }
+        std::rename(path->c_str(), (path.get() + ".bak").c_str());
+        GDK_RUNTIME_ASSERT_MSG(std::rename(tmp_path.c_str(), path->c_str()) == 0, "Failed to replace db file");
         m_require_write = false;
     }
 
@@ -283,15 +376,23 @@ namespace sdk {
             return;
         }
         m_type = type;
-        auto plaintext = [&encryption_key, &path]() -> boost::optional<std::vector<unsigned char>> {
+        const auto decrypt = [&encryption_key](const std::string& file) -> boost::optional<std::vector<unsigned char>> {
             try {
-                return openssl_decrypt(sha256(encryption_key), path.get());
+                return openssl_decrypt(sha256(encryption_key), file);
             } catch (const std::exception& ex) {
-                GDK_LOG_SEV(log_level::info) << "Bad decryption for file " << path.get() << " error " << ex.what();
-                unlink(path->c_str());
+                GDK_LOG_SEV(log_level::info) << "Bad decryption for file " << file << " error " << ex.what();
+                unlink(file.c_str());
                 return boost::none;
             }
-        }();
+        };
+        auto plaintext = decrypt(path.get());
+        if (!plaintext) {
+            // Fall back to the previous version if the latest is missing or corrupt
+            plaintext = decrypt(path.get() + ".bak");
+            if (plaintext) {
+                m_require_write = true;
+            }
+        }
 
         if (!plaintext) {
             return;
@@ -313,6 +414,122 @@ namespace sdk {
             = gsl::finally([backup] { GDK_RUNTIME_ASSERT(sqlite3_backup_finish(backup) == SQLITE_OK); });
         GDK_RUNTIME_ASSERT(sqlite3_backup_step(backup, -1) == SQLITE_DONE);
         GDK_LOG_SEV(log_level::info) << "sqlite loaded correctly " << path.get();
+        create_tables(m_db.get());
+
+        if (static_cast<sqlite3_int64>(plaintext->size()) > MAX_DB_SIZE) {
+            compact();
+            save_db(encryption_key);
+        }
+    }
+
+    // Returns false if the cache couldn't be written under the new key, in
+    // which case it remains stored under the old key
+    bool cache::rekey_db(byte_span_t old_encryption_key, byte_span_t new_encryption_key)
+    {
+        GDK_RUNTIME_ASSERT(!old_encryption_key.empty() && !new_encryption_key.empty());
+        const auto path = get_persistent_storage_file(old_encryption_key, m_network_name, m_type);
+        if (!path) {
+            return true; // Nothing is stored
+        }
+        // The file name is derived from the key, so saving under the new key
+        // writes a new file. Only remove the old one once that has succeeded
+        m_require_write = true;
+        save_db(new_encryption_key);
+        if (m_require_write) {
+            return false;
+        }
+        unlink(path->c_str());
+        unlink((path.get() + ".bak").c_str());
+        return true;
+    }
+
+    // Whether encryption_key is the key in use for the cache of base_key. The first
+    // key seen is recorded, so that e.g. a mistyped cache password is an error rather
+    // than silently opening a new, empty cache under a different file name
+    bool cache::check_encryption_key(byte_span_t base_key, byte_span_t encryption_key, const uint32_t type)
+    {
+        GDK_RUNTIME_ASSERT(!base_key.empty() && !encryption_key.empty());
+        const auto path = get_key_check_file(base_key, m_network_name, type);
+        if (!path) {
+            return true; // Nothing is stored
+        }
+        std::ifstream f(path.get(), f.in | f.binary);
+        if (!f.is_open()) {
+            m_type = type;
+            set_encryption_key_check(base_key, encryption_key);
+            return true;
+        }
+        const std::vector<unsigned char> stored((std::istreambuf_iterator<char>(f)), std::istreambuf_iterator<char>());
+        const auto expected = get_key_check(encryption_key);
+        return std::equal(stored.begin(), stored.end(), expected.begin(), expected.end());
+    }
+
+    void cache::set_encryption_key_check(byte_span_t base_key, byte_span_t encryption_key)
+    {
+        GDK_RUNTIME_ASSERT(!base_key.empty() && !encryption_key.empty());
+        const auto path = get_key_check_file(base_key, m_network_name, m_type);
+        if (!path) {
+            return;
+        }
+        const auto check = get_key_check(encryption_key);
+        const std::string tmp_path = path.get() + ".tmp";
+        {
+            std::ofstream f(tmp_path, f.out | f.binary | f.trunc);
+            f.write(reinterpret_cast<const char*>(check.data()), check.size());
+            f.close();
+            GDK_RUNTIME_ASSERT_MSG(!f.fail() && sync_file(tmp_path), "Failed to write cache key check");
+        }
+        GDK_RUNTIME_ASSERT_MSG(std::rename(tmp_path.c_str(), path->c_str()) == 0, "Failed to write cache key check");
+    }
+
+    nlohmann::json cache::get_stats()
+    {
+        nlohmann::json tables = nlohmann::json::object();
+        for (const char* table :
+            { "LiquidOutput", "KeyValue", "LiquidBlindingNonce", "EventJournal", "GeneratedAddress" }) {
+            const std::string sql = std::string("SELECT COUNT(*) FROM ") + table + ";";
+            const auto stmt = std::unique_ptr<sqlite3_stmt>(get_stmt(m_db.get(), sql.c_str()));
+            GDK_RUNTIME_ASSERT(sqlite3_step(stmt.get()) == SQLITE_ROW);
+            tables[table] = { { "rows", sqlite3_column_int64(stmt.get(), 0) } };
+        }
+
+        // Break down the key/value store by the kind of data stored
+        nlohmann::json keyvalues = nlohmann::json::object();
+        const auto stmt = std::unique_ptr<sqlite3_stmt>(
+            get_stmt(m_db.get(), "SELECT CAST(key AS TEXT), length(key) + length(value) FROM KeyValue;"));
+        int rc;
+        while ((rc = sqlite3_step(stmt.get())) == SQLITE_ROW) {
+            const auto key = reinterpret_cast<const char*>(sqlite3_column_text(stmt.get(), 0));
+            auto& entry = keyvalues[get_key_prefix(key ? key : "")];
+            if (entry.is_null()) {
+                entry = { { "rows", 0 }, { "bytes", 0 } };
+            }
+            entry["rows"] = entry["rows"].get<int64_t>() + 1;
+            entry["bytes"] = entry["bytes"].get<int64_t>() + sqlite3_column_int64(stmt.get(), 1);
+        }
+        GDK_RUNTIME_ASSERT(rc == SQLITE_DONE);
+
+        return { { "size", get_db_size(m_db.get()) }, { "max_size", MAX_DB_SIZE }, { "tables", tables },
+            { "keyvalues", keyvalues } };
+    }
+
+    // Remove data that can be fetched again when needed and reclaim its space
+    void cache::compact()
+    {
+        const auto before = get_db_size(m_db.get());
+        for (const auto& prefix : REFETCHABLE_PREFIXES) {
+            const auto stmt = std::unique_ptr<sqlite3_stmt>(
+                get_stmt(m_db.get(), "DELETE FROM KeyValue WHERE substr(CAST(key AS TEXT), 1, ?2) = ?1;"));
+            const int prefix_len = prefix.size();
+            GDK_RUNTIME_ASSERT(sqlite3_bind_text(stmt.get(), 1, prefix.data(), prefix_len, SQLITE_STATIC) == SQLITE_OK);
+            GDK_RUNTIME_ASSERT(sqlite3_bind_int(stmt.get(), 2, prefix_len) == SQLITE_OK);
+            GDK_RUNTIME_ASSERT(sqlite3_step(stmt.get()) == SQLITE_DONE);
+        }
+        char* err_msg = nullptr;
+        const int rc = sqlite3_exec(m_db.get(), "VACUUM;", 0, 0, &err_msg);
+        GDK_RUNTIME_ASSERT_MSG(rc == SQLITE_OK, err_msg ? err_msg : "VACUUM failed");
+        m_require_write = true;
+        GDK_LOG_SEV(log_level::info) << "Compacted db from " << before << " to " << get_db_size(m_db.get()) << " bytes";
     }
 
     void cache::clear_keyvalue(const std::string& key)
@@ -322,6 +539,9 @@ namespace sdk {
         const auto key_span = ustring_span(key);
         bind_blob(m_stmt_keyvalue_delete.get(), 1, key_span);
         GDK_RUNTIME_ASSERT(sqlite3_step(m_stmt_keyvalue_delete.get()) == SQLITE_DONE);
+        if (sqlite3_changes(m_db.get()) != 0) {
+            m_require_write = true;
+        }
     }
 
     boost::optional<std::vector<unsigned char>> cache::get(const std::string& key)
@@ -404,6 +624,11 @@ namespace sdk {
     void cache::upsert_keyvalue(const std::string& key, byte_span_t value)
     {
         GDK_RUNTIME_ASSERT(!key.empty() && !value.empty());
+        // Avoid re-writing the db when a value is stored again unchanged
+        const auto existing = get(key);
+        if (existing && std::equal(existing->begin(), existing->end(), value.begin(), value.end())) {
+            return;
+        }
         const auto _stmt_clean = gsl::finally([this] { stmt_check_clean(m_stmt_keyvalue_upsert.get()); });
         const auto key_span = ustring_span(key);
         bind_blob(m_stmt_keyvalue_upsert.get(), 1, key_span);
@@ -412,6 +637,66 @@ namespace sdk {
         m_require_write = true;
     }
 
+    void cache::insert_event(const nlohmann::json& event)
+    {
+        GDK_RUNTIME_ASSERT(event.is_object());
+        const auto _stmt_clean = gsl::finally([this] { stmt_check_clean(m_stmt_event_insert.get()); });
+        const auto data = nlohmann::json::to_msgpack(event);
+        bind_blob(m_stmt_event_insert.get(), 1, data);
+        GDK_RUNTIME_ASSERT(sqlite3_step(m_stmt_event_insert.get()) == SQLITE_DONE);
+        m_require_write = true;
+    }
+
+    nlohmann::json cache::get_events()
+    {
+        const auto _stmt_clean = gsl::finally([this] { stmt_check_clean(m_stmt_event_search.get()); });
+        nlohmann::json events = nlohmann::json::array();
+        int rc;
+        while ((rc = sqlite3_step(m_stmt_event_search.get())) == SQLITE_ROW) {
+            const auto res = reinterpret_cast<const unsigned char*>(sqlite3_column_blob(m_stmt_event_search.get(), 1));
+            const auto bytes = sqlite3_column_bytes(m_stmt_event_search.get(), 1);
+            GDK_RUNTIME_ASSERT(res);
+            nlohmann::json event = nlohmann::json::from_msgpack(res, res + bytes);
+            event["id"] = sqlite3_column_int64(m_stmt_event_search.get(), 0);
+            events.push_back(std::move(event));
+        }
+        GDK_RUNTIME_ASSERT(rc == SQLITE_DONE);
+        return events;
+    }
+
+    void cache::insert_generated_address(
+        const std::string& address, uint32_t subaccount, const nlohmann::json& details)
+    {
+        GDK_RUNTIME_ASSERT(!address.empty() && details.is_object());
+        const auto _stmt_clean = gsl::finally([this] { stmt_check_clean(m_stmt_generated_address_insert.get()); });
+        const auto address_span = ustring_span(address);
+        bind_blob(m_stmt_generated_address_insert.get(), 1, address_span);
+        GDK_RUNTIME_ASSERT(sqlite3_bind_int(m_stmt_generated_address_insert.get(), 2, subaccount) == SQLITE_OK);
+        const auto data = nlohmann::json::to_msgpack(details);
+        bind_blob(m_stmt_generated_address_insert.get(), 3, data);
+        GDK_RUNTIME_ASSERT(sqlite3_step(m_stmt_generated_address_insert.get()) == SQLITE_DONE);
+        if (sqlite3_changes(m_db.get()) != 0) {
+            m_require_write = true; // Not already recorded
+        }
+    }
+
+    nlohmann::json cache::get_generated_addresses(uint32_t subaccount)
+    {
+        const auto _stmt_clean = gsl::finally([this] { stmt_check_clean(m_stmt_generated_address_search.get()); });
+        GDK_RUNTIME_ASSERT(sqlite3_bind_int(m_stmt_generated_address_search.get(), 1, subaccount) == SQLITE_OK);
+        nlohmann::json addresses = nlohmann::json::array();
+        int rc;
+        while ((rc = sqlite3_step(m_stmt_generated_address_search.get())) == SQLITE_ROW) {
+            const auto res
+                = reinterpret_cast<const unsigned char*>(sqlite3_column_blob(m_stmt_generated_address_search.get(), 0));
+            const auto bytes = sqlite3_column_bytes(m_stmt_generated_address_search.get(), 0);
+            GDK_RUNTIME_ASSERT(res);
+            addresses.push_back(nlohmann::json::from_msgpack(res, res + bytes));
+        }
+        GDK_RUNTIME_ASSERT(rc == SQLITE_DONE);
+        return addresses;
+    }
+
     void cache::insert_liquidblindingnonce(byte_span_t pubkey, byte_span_t script, byte_span_t nonce)
     {
         GDK_RUNTIME_ASSERT(!pubkey.empty() && !script.empty() && !nonce.empty());
@@ -30,8 +30,17 @@ namespace sdk {
         bool has_liquidblindingnonce(byte_span_t pubkey, byte_span_t script);
         boost::optional<std::vector<unsigned char>> get(const std::string& key);
         void clear_keyvalue(const std::string& key);
+        void insert_event(const nlohmann::json& event);
+        nlohmann::json get_events();
+        void insert_generated_address(const std::string& address, uint32_t subaccount, const nlohmann::json& details);
+        nlohmann::json get_generated_addresses(uint32_t subaccount);
         void save_db(byte_span_t encryption_key);
         void load_db(byte_span_t encryption_key, const uint32_t type);
+        bool rekey_db(byte_span_t old_encryption_key, byte_span_t new_encryption_key);
+        bool check_encryption_key(byte_span_t base_key, byte_span_t encryption_key, const uint32_t type);
+        void set_encryption_key_check(byte_span_t base_key, byte_span_t encryption_key);
+        nlohmann::json get_stats();
+        void compact();
 
     private:
         uint32_t m_type = 0;
@@ -47,6 +56,10 @@ namespace sdk {
         std::unique_ptr<sqlite3_stmt> m_stmt_keyvalue_upsert;
         std::unique_ptr<sqlite3_stmt> m_stmt_keyvalue_search;
         std::unique_ptr<sqlite3_stmt> m_stmt_keyvalue_delete;
+        std::unique_ptr<sqlite3_stmt> m_stmt_event_insert;
+        std::unique_ptr<sqlite3_stmt> m_stmt_event_search;
+        std::unique_ptr<sqlite3_stmt> m_stmt_generated_address_insert;
+        std::unique_ptr<sqlite3_stmt> m_stmt_generated_address_search;
     };
 
 } // namespace sdk
@@ -1,7 +1,10 @@
 #include <array>
 #include <cstdio>
+#include <ctime>
 #include <fstream>
+#include <future>
 #include <map>
+#include <set>
 #include <string>
 #include <thread>
 #include <vector>
@@ -95,8 +98,8 @@ namespace sdk {
 
         static const std::string MASKED_GAUTH_SEED("***");
 
-        static const uint32_t DEFAULT_MIN_FEE = 1000; // 1 satoshi/byte
         static const uint32_t NUM_FEE_ESTIMATES = 25; // Min fee followed by blocks 1-24
+        static const uint64_t DEFAULT_MAX_FEE_ESTIMATES_AGE = 30 * 60; // Age in seconds before estimates are stale
 
         // networking defaults
         static const uint32_t DEFAULT_PING = 20; // ping message interval in seconds
@@ -105,6 +108,7 @@ namespace sdk {
         static const uint32_t DEFAULT_KEEPCNT = 2; // tcp unanswered heartbeats
         static const uint32_t DEFAULT_DISCONNECT_WAIT = 2; // maximum wait time on disconnect in seconds
         static const uint32_t DEFAULT_TOR_SOCKS_WAIT = 15; // maximum timeout for the tor socks to get ready
+        static const uint32_t DEFAULT_REQUEST_TIMEOUT = 10; // maximum wait time for a server response in seconds
 
         // TODO: too slow. lacks validation.
         static std::array<unsigned char, SHA256_LEN> uint256_to_base256(const std::string& input)
@@ -263,6 +267,153 @@ namespace sdk {
             const auto& user_agent = supports_csv ? USER_AGENT : USER_AGENT_NO_CSV;
             return user_agent + version;
         }
+
+        // Return the issuer domain of a registry asset if its contract is committed to by
+        // its issuance, or null if the registry data can't be verified
+        static nlohmann::json get_verified_issuer_domain(const std::string& asset_id, const nlohmann::json& asset)
+        {
+            try {
+                const auto& contract = asset.at("contract");
+                const std::string domain = contract.at("entity").at("domain");
+                if (asset.at("entity").at("domain") != domain) {
+                    return nullptr;
+                }
+                // The contract hash is the hash of the canonical (sorted, compact, ascii) contract JSON
+                const auto contract_hash = sha256(ustring_span(contract.dump(-1, ' ', true)));
+                const auto& prevout = asset.at("issuance_prevout");
+                const auto txhash = h2b_rev(prevout.at("txid").get<std::string>());
+                const auto entropy = tx_elements_issuance_generate_entropy(txhash, prevout.at("vout"), contract_hash);
+                if (b2h_rev(tx_elements_issuance_calculate_asset(entropy)) == asset_id) {
+                    return domain;
+                }
+            } catch (const std::exception&) {
+                // Missing or malformed registry data
+            }
+            return nullptr;
+        }
+
+        // Return the outpoints spent by a transaction, as "txhash:index"
+        static std::set<std::string> get_spent_outpoints(const wally_tx_ptr& tx)
+        {
+            std::set<std::string> outpoints;
+            for (size_t i = 0; i < tx->num_inputs; ++i) {
+                const auto& input = tx->inputs[i];
+                outpoints.insert(b2h_rev(input.txhash) + ":" + std::to_string(input.index));
+            }
+            return outpoints;
+        }
+
+        static bool spends_wallet_coins(const nlohmann::json& tx_details)
+        {
+            const auto& inputs = tx_details.at("inputs");
+            return std::any_of(inputs.begin(), inputs.end(),
+                [](const auto& input) { return json_get_value(input, "is_relevant", false); });
+        }
+
+        // Return the change a transaction makes to the wallets balance of each asset.
+        // Amounts are unsigned, with the assets a mixed tx receives in incoming_assets
+        static std::map<std::string, int64_t> get_balance_changes(const nlohmann::json& tx_details)
+        {
+            const bool is_incoming = tx_details.at("type") == "incoming";
+            const auto incoming_assets = tx_details.value("incoming_assets", std::vector<std::string>());
+            std::map<std::string, int64_t> changes;
+            for (const auto& asset : tx_details.at("satoshi").items()) {
+                const int64_t satoshi = asset.value().get<int64_t>();
+                const bool is_received = is_incoming
+                    || std::find(incoming_assets.begin(), incoming_assets.end(), asset.key()) != incoming_assets.end();
+                changes.emplace(asset.key(), is_received ? satoshi : -satoshi);
+            }
+            return changes;
+        }
+
+        static std::vector<nlohmann::json> group_replaced_transactions(std::vector<nlohmann::json>& tx_list)
+        {
+            // Map each replaced tx to the tx that replaced it
+            std::map<std::string, std::string> replacements;
+            for (const auto& tx_details : tx_list) {
+                const std::string replaced_by = json_get_value(tx_details, "replaced_by");
+                if (!replaced_by.empty()) {
+                    replacements.emplace(tx_details.at("txhash"), replaced_by);
+                }
+            }
+
+            // Follow a chain of replacements (e.g. a payment bumped several times)
+            // through to the tx that finally replaced it
+            auto&& get_final_replacement = [&replacements](std::string txhash) {
+                for (size_t i = 0; i <= replacements.size(); ++i) {
+                    const auto p = replacements.find(txhash);
+                    if (p == replacements.end()) {
+                        break;
+                    }
+                    txhash = p->second;
+                }
+                return txhash;
+            };
+
+            std::vector<nlohmann::json> grouped;
+            grouped.reserve(tx_list.size() - replacements.size());
+            std::map<std::string, size_t> positions;
+            std::vector<nlohmann::json> replaced;
+            for (auto& tx_details : tx_list) {
+                const std::string txhash = tx_details.at("txhash");
+                if (replacements.find(txhash) != replacements.end()) {
+                    replaced.emplace_back(std::move(tx_details));
+                } else {
+                    tx_details["replaces"] = nlohmann::json::array();
+                    positions.emplace(txhash, grouped.size());
+                    grouped.emplace_back(std::move(tx_details));
+                }
+            }
+
+            // Nest replaced txs under their final replacement so that a single
+            // user payment is listed once, with its predecessors marked as replaced
+            // TODO: Mark replaced txs whose replacement isn't in our list as double spent
+            for (auto& tx_details : replaced) {
+                const auto p = positions.find(get_final_replacement(tx_details.at("txhash")));
+                if (p != positions.end()) {
+                    tx_details["status"] = "replaced";
+                    grouped[p->second]["replaces"].emplace_back(std::move(tx_details));
+                }
+            }
+            return grouped;
+        }
+
+        // Mix the user's cache password, if any, into the key used to encrypt the
+        // cache, so that access to the wallet's xpubs alone can't decrypt it
+        std::array<unsigned char, PBKDF2_HMAC_SHA512_LEN> get_cache_key(
+            const std::array<unsigned char, PBKDF2_HMAC_SHA512_LEN>& base_key, const std::string& password)
+        {
+            if (password.empty()) {
+                return base_key;
+            }
+            std::vector<unsigned char> derived(PBKDF2_HMAC_SHA512_LEN);
+            scrypt(ustring_span(password), base_key, 16384, 8, 8, derived);
+            std::array<unsigned char, PBKDF2_HMAC_SHA512_LEN> key;
+            std::copy(derived.begin(), derived.end(), key.begin());
+            return key;
+        }
+
+        // The base key of the cache: a host provided key is mixed with the wallet's own
+        // local key, so that wallets given the same host key still use separate caches
+        std::array<unsigned char, PBKDF2_HMAC_SHA512_LEN> get_cache_base_key(
+            const boost::optional<std::array<unsigned char, PBKDF2_HMAC_SHA512_LEN>>& host_key,
+            byte_span_t wallet_key)
+        {
+            GDK_RUNTIME_ASSERT(wallet_key.size() == PBKDF2_HMAC_SHA512_LEN);
+            if (host_key) {
+                return hmac_sha512(host_key.get(), wallet_key);
+            }
+            std::array<unsigned char, PBKDF2_HMAC_SHA512_LEN> key;
+            std::copy(wallet_key.begin(), wallet_key.end(), key.begin());
+            return key;
+        }
+
+        // Key under which a failure to unblind an output with our keys is cached
+        std::string get_unblind_failure_key(const nlohmann::json& utxo)
+        {
+            const std::string txhash = utxo.at("txhash");
+            return "unblindfailed:" + txhash + ":" + std::to_string(utxo.at("pt_idx").get<uint32_t>());
+        }
     } // namespace
 
     uint32_t websocket_rng_type::operator()() const
@@ -296,9 +447,11 @@ namespace sdk {
         , m_ping_timer(m_io)
         , m_notification_handler(nullptr)
         , m_notification_context(nullptr)
-        , m_min_fee_rate(DEFAULT_MIN_FEE)
+        , m_min_fee_rate(m_net_params.min_fee_rate())
+        , m_fiat_rate_updated(0)
         , m_earliest_block_time(0)
         , m_next_subaccount(0)
+        , m_fee_estimates_updated(0)
         , m_block_height(0)
         , m_system_message_id(0)
         , m_system_message_ack_id(0)
@@ -308,6 +461,7 @@ namespace sdk {
         , m_tx_last_notification(std::chrono::system_clock::now())
         , m_cache(net_params.at("name"))
         , m_user_agent(net_params.value("user_agent", GDK_COMMIT))
+        , m_request_timeout(std::max(net_params.value("request_timeout", DEFAULT_REQUEST_TIMEOUT), 1u))
     {
         const auto log_level = net_params.value("log_level", "none");
         m_log_level = log_level == "none"
@@ -556,6 +710,12 @@ namespace sdk {
 
     void ga_session::set_ping_fail_handler(ping_fail_t handler) { m_ping_fail_handler = std::move(handler); }
 
+    void ga_session::cancel_pending_requests()
+    {
+        GDK_LOG_SEV(log_level::info) << "cancelling pending requests";
+        ++m_cancel_id;
+    }
+
     void ga_session::emit_notification(std::string event, nlohmann::json details)
     {
         asio::post(m_pool, [this, event, details] {
@@ -577,8 +737,9 @@ namespace sdk {
 
         if (is_connected()) {
             GDK_LOG_SEV(log_level::info) << "attempting to reconnect but transport still connected. backing off...";
-            emit_notification(
-                "network", { { "connected", true }, { "login_required", false }, { "heartbeat_timeout", true } });
+            emit_notification("network",
+                { { "connected", true }, { "login_required", false }, { "heartbeat_timeout", true },
+                    { "state", "degraded" } });
             return;
         }
 
@@ -600,7 +761,7 @@ namespace sdk {
             for (;;) {
                 const auto backoff_time = bo.backoff(n++);
                 nlohmann::json network_status = { { "connected", false }, { "elapsed", bo.elapsed().count() },
-                    { "waiting", bo.waiting().count() }, { "limit", bo.limit_reached() } };
+                    { "waiting", bo.waiting().count() }, { "limit", bo.limit_reached() }, { "state", "offline" } };
                 emit_notification("network", network_status);
 
                 if (!m_network_control.retrying(backoff_time)) {
@@ -649,8 +810,9 @@ namespace sdk {
             if (!logged_in) {
                 on_failed_login();
             }
-            emit_notification(
-                "network", { { "connected", true }, { "login_required", !logged_in }, { "heartbeat_timeout", false } });
+            emit_notification("network",
+                { { "connected", true }, { "login_required", !logged_in }, { "heartbeat_timeout", false },
+                    { "state", "connected" } });
 
             return true;
         } catch (const std::exception&) {
@@ -789,8 +951,15 @@ namespace sdk {
             nlohmann::json json_assets;
             if (assets.find("error") == assets.end()) {
                 json_assets = assets;
+                for (auto& asset : json_assets.items()) {
+                    auto& asset_info = asset.value();
+                    if (asset_info.is_object()) {
+                        asset_info["verified_issuer_domain"] = get_verified_issuer_domain(asset.key(), asset_info);
+                    }
+                }
                 json_assets.update({ { m_net_params.policy_asset(),
-                    { { "asset_id", m_net_params.policy_asset() }, { "name", "btc" } } } });
+                    { { "asset_id", m_net_params.policy_asset() }, { "name", "btc" },
+                        { "verified_issuer_domain", nullptr } } } });
             }
             result["assets"] = json_assets;
         }
@@ -802,6 +971,188 @@ namespace sdk {
         return result;
     }
 
+    nlohmann::json ga_session::lookup_asset(const nlohmann::json& params)
+    {
+        GDK_RUNTIME_ASSERT(m_net_params.liquid());
+        const std::string asset_id = params.at("asset_id");
+        GDK_RUNTIME_ASSERT_MSG(h2b(asset_id).size() == ASSET_TAG_LEN, "Invalid asset id");
+
+        if (asset_id == m_net_params.policy_asset()) {
+            return { { "asset_id", asset_id }, { "name", "btc" }, { "verified_issuer_domain", nullptr } };
+        }
+
+        nlohmann::json asset_info;
+        {
+            locker_t locker(m_mutex);
+            asset_info = json_get_value(get_cached_assets(locker, { asset_id }), asset_id, nlohmann::json());
+        }
+
+        if (asset_info.is_null()) {
+            asset_info = http_get(
+                { { "uri", m_net_params.get_registry_connection_string(m_use_tor) }, { "target", "/" + asset_id } });
+            if (asset_info.contains("error") || asset_info.contains("body")) {
+                // Not found, or the registry is unreachable
+                return { { "error", "asset not found" } };
+            }
+            GDK_RUNTIME_ASSERT_MSG(asset_info.value("asset_id", std::string()) == asset_id, "asset id mismatch");
+            asset_info.erase("last_modified");
+            locker_t locker(m_mutex);
+            m_cache.upsert_keyvalue("asset" + asset_id, nlohmann::json::to_msgpack(asset_info));
+            if (m_local_encryption_key) {
+                m_cache.save_db(m_local_encryption_key.get());
+            }
+        }
+        asset_info["verified_issuer_domain"] = get_verified_issuer_domain(asset_id, asset_info);
+        return asset_info;
+    }
+
+    nlohmann::json ga_session::get_imported_blinders(locker_t& locker)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        const auto value = m_cache.get("importedblinders");
+        if (!value) {
+            return nlohmann::json::object();
+        }
+        return nlohmann::json::from_msgpack(value->begin(), value->end());
+    }
+
+    void ga_session::import_blinders(const nlohmann::json& details)
+    {
+        GDK_RUNTIME_ASSERT(m_net_params.liquid());
+
+        // Validate the entries before storing them
+        nlohmann::json imported = nlohmann::json::object();
+        for (const auto& blinder : details.at("blinders")) {
+            const std::string txhash = blinder.at("txhash");
+            const uint32_t pt_idx = blinder.at("pt_idx");
+            const std::string asset_id = blinder.at("asset_id");
+            const std::string abf = blinder.at("abf");
+            const std::string vbf = blinder.at("vbf");
+            const amount::value_type satoshi = blinder.at("satoshi");
+            GDK_RUNTIME_ASSERT_MSG(h2b(txhash).size() == SHA256_LEN, "invalid txhash");
+            GDK_RUNTIME_ASSERT_MSG(h2b(asset_id).size() == ASSET_TAG_LEN, "invalid asset_id");
+            GDK_RUNTIME_ASSERT_MSG(h2b(abf).size() == sizeof(abf_t), "invalid abf");
+            GDK_RUNTIME_ASSERT_MSG(h2b(vbf).size() == sizeof(vbf_t), "invalid vbf");
+            imported[txhash + ":" + std::to_string(pt_idx)]
+                = { { "asset_id", asset_id }, { "satoshi", satoshi }, { "abf", abf }, { "vbf", vbf } };
+        }
+
+        locker_t locker(m_mutex);
+        auto blinders = get_imported_blinders(locker);
+        blinders.update(imported);
+        m_cache.upsert_keyvalue("importedblinders", nlohmann::json::to_msgpack(blinders));
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
+    }
+
+    // Unblind an output using imported blinding factors, if they match its commitments
+    bool ga_session::unblind_utxo_with_imported_blinders(nlohmann::json& utxo)
+    {
+        if (!utxo.contains("txhash")) {
+            return false;
+        }
+        const std::string txhash_hex = utxo.at("txhash");
+        const std::string key = txhash_hex + ":" + std::to_string(utxo.at("pt_idx").get<uint32_t>());
+        nlohmann::json blinder;
+        {
+            locker_t locker(m_mutex);
+            blinder = json_get_value(get_imported_blinders(locker), key, nlohmann::json());
+        }
+        if (blinder.is_null()) {
+            return false;
+        }
+
+        const auto generator = asset_generator_from_bytes(h2b_rev(blinder.at("asset_id")), h2b(blinder.at("abf")));
+        const auto commitment = asset_value_commitment(blinder.at("satoshi"), h2b(blinder.at("vbf")), generator);
+        if (b2h(generator) != utxo.at("asset_tag") || b2h(commitment) != utxo.at("commitment")) {
+            return false;
+        }
+
+        utxo.update(blinder);
+        utxo["confidential"] = true;
+        const auto txhash = h2b(utxo.at("txhash"));
+        const uint32_t vout = utxo.at("pt_idx");
+        locker_t locker(m_mutex);
+        if (!m_cache.get_liquidoutput(txhash, vout)) {
+            m_cache.insert_liquidoutput(txhash, vout, utxo);
+        }
+        return true;
+    }
+
+    nlohmann::json ga_session::get_issuances(locker_t& locker)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        const auto value = m_cache.get("issuances");
+        if (!value) {
+            return nlohmann::json::object();
+        }
+        return nlohmann::json::from_msgpack(value->begin(), value->end());
+    }
+
+    // Return the known registry entries for the given assets, from the cached
+    // registry or from assets previously looked up individually
+    nlohmann::json ga_session::get_cached_assets(locker_t& locker, const std::set<std::string>& asset_ids)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+
+        nlohmann::json result = nlohmann::json::object();
+        nlohmann::json assets;
+        const auto index = m_cache.get("index");
+        if (index) {
+            assets = nlohmann::json::from_msgpack(index->begin(), index->end());
+        }
+        for (const auto& asset_id : asset_ids) {
+            const auto p = assets.find(asset_id);
+            if (p != assets.end() && p->is_object()) {
+                result[asset_id] = *p;
+                continue;
+            }
+            const auto value = m_cache.get("asset" + asset_id);
+            if (value) {
+                result[asset_id] = nlohmann::json::from_msgpack(value->begin(), value->end());
+            }
+        }
+        return result;
+    }
+
+    // Return the display metadata for the assets in a satoshi map, keyed as in the map
+    nlohmann::json ga_session::get_assets_metadata(locker_t& locker, const nlohmann::json& satoshi)
+    {
+        const std::string policy_asset = m_net_params.policy_asset();
+        std::set<std::string> asset_ids;
+        for (const auto& item : satoshi.items()) {
+            asset_ids.insert(item.key() == "btc" ? policy_asset : item.key());
+        }
+        const auto assets = get_cached_assets(locker, asset_ids);
+
+        nlohmann::json result = nlohmann::json::object();
+        for (const auto& item : satoshi.items()) {
+            const auto& key = item.key();
+            if (key == "btc") {
+                // Prefer the registry's ticker, falling back to the network's
+                const auto p = assets.find(policy_asset);
+                std::string ticker = p == assets.end() ? std::string() : json_get_value(*p, "ticker");
+                if (ticker.empty()) {
+                    ticker = m_net_params.policy_asset_ticker();
+                }
+                result[key] = { { "asset_id", policy_asset }, { "name", "btc" }, { "ticker", ticker },
+                    { "precision", 8 } };
+                continue;
+            }
+            const auto p = assets.find(key);
+            if (p == assets.end()) {
+                // Unknown asset: display as a raw amount
+                result[key] = { { "asset_id", key }, { "name", nullptr }, { "ticker", nullptr }, { "precision", 0 } };
+                continue;
+            }
+            result[key] = { { "asset_id", key }, { "name", json_get_value(*p, "name", nlohmann::json()) },
+                { "ticker", json_get_value(*p, "ticker", nlohmann::json()) },
+                { "precision", json_get_value(*p, "precision", 0) } };
+        }
+        return result;
+    }
+
     ga_session::nlocktime_t ga_session::get_upcoming_nlocktime()
     {
         auto upcoming = [this]() -> boost::optional<nlohmann::json> {
@@ -861,6 +1212,16 @@ namespace sdk {
         stop_reconnect();
         m_pool.join();
         on_failed_login();
+        {
+            // Don't keep the host provided cache key or password once logged out
+            locker_t locker(m_mutex);
+            if (m_cache_encryption_key) {
+                wally_bzero(m_cache_encryption_key->data(), m_cache_encryption_key->size());
+                m_cache_encryption_key = boost::none;
+            }
+            wally_bzero(&m_cache_password[0], m_cache_password.size());
+            m_cache_password.clear();
+        }
         unsubscribe();
         disconnect();
         // FIXME: securely destroy all held data
@@ -903,7 +1264,7 @@ namespace sdk {
                 if (actual_block > 0 && actual_block <= NUM_FEE_ESTIMATES - 1) {
                     const long long satoshi_per_k = std::lround(btc_per_k * amount::coin_value);
                     const long long uint32_t_max = std::numeric_limits<uint32_t>::max();
-                    if (satoshi_per_k >= DEFAULT_MIN_FEE && satoshi_per_k <= uint32_t_max) {
+                    if (satoshi_per_k >= m_net_params.min_fee_rate() && satoshi_per_k <= uint32_t_max) {
                         ordered_estimates[actual_block] = static_cast<uint32_t>(satoshi_per_k);
                     }
                 }
@@ -928,6 +1289,8 @@ namespace sdk {
             }
 
             std::swap(m_fee_estimates, new_estimates);
+            const auto now = std::chrono::system_clock::now().time_since_epoch();
+            m_fee_estimates_updated = std::chrono::duration_cast<std::chrono::seconds>(now).count();
         }
         return m_fee_estimates;
     }
@@ -1028,7 +1391,7 @@ namespace sdk {
         m_ga_pubkeys = std::make_unique<ga_pubkeys>(m_net_params, m_gait_path);
         m_recovery_pubkeys = std::make_unique<ga_user_pubkeys>(m_net_params);
 
-        const uint32_t min_fee_rate = m_login_data["min_fee"];
+        const uint32_t min_fee_rate = std::max<uint32_t>(m_login_data["min_fee"], m_net_params.min_fee_rate());
         if (min_fee_rate != m_min_fee_rate) {
             m_min_fee_rate = min_fee_rate;
             m_fee_estimates.assign(NUM_FEE_ESTIMATES, m_min_fee_rate);
@@ -1113,12 +1476,31 @@ namespace sdk {
     void ga_session::update_fiat_rate(ga_session::locker_t& locker, const std::string& rate_str)
     {
         GDK_RUNTIME_ASSERT(locker.owns_lock());
+        const auto now = std::chrono::system_clock::now().time_since_epoch();
         try {
             m_fiat_rate = amount::format_amount(rate_str, 8);
+            m_fiat_rate_updated = std::chrono::duration_cast<std::chrono::seconds>(now).count();
+            if (m_local_encryption_key) {
+                // Keep the latest rate to fall back on if the server can't provide one
+                const nlohmann::json cached = { { "currency", m_fiat_currency }, { "rate", m_fiat_rate },
+                    { "updated", m_fiat_rate_updated } };
+                m_cache.upsert_keyvalue("fiatrate", nlohmann::json::to_msgpack(cached));
+            }
         } catch (const std::exception& e) {
             m_fiat_rate.clear();
+            m_fiat_rate_updated = 0;
             GDK_LOG_SEV(log_level::error)
                 << "failed to update fiat rate from string '" << rate_str << "': " << e.what();
+            if (m_local_encryption_key) {
+                const auto value = m_cache.get("fiatrate");
+                if (value) {
+                    const auto cached = nlohmann::json::from_msgpack(value->begin(), value->end());
+                    if (cached.at("currency") == m_fiat_currency) {
+                        m_fiat_rate = cached.at("rate");
+                        m_fiat_rate_updated = cached.at("updated");
+                    }
+                }
+            }
         }
     }
 
@@ -1132,6 +1514,89 @@ namespace sdk {
         }
     }
 
+    nlohmann::json ga_session::estimate_transaction_fee(const nlohmann::json& details) const
+    {
+        const size_t weight = estimate_tx_weight(m_net_params, details.at("inputs"), details.at("outputs"));
+        const size_t vsize = tx_vsize_from_weight(weight);
+
+        const amount min_fee_rate = get_min_fee_rate();
+        const auto fee_rate_p = details.find("fee_rate");
+        amount fee_rate = fee_rate_p != details.end() ? amount(*fee_rate_p) : get_default_fee_rate();
+        if (fee_rate < min_fee_rate) {
+            fee_rate = min_fee_rate;
+        }
+        const auto fee = static_cast<double>(vsize) * fee_rate.value() / 1000.0;
+        const auto rounded_fee = static_cast<amount::value_type>(std::ceil(fee));
+
+        return { { "transaction_weight", weight }, { "transaction_vsize", vsize }, { "fee_rate", fee_rate.value() },
+            { "fee", rounded_fee } };
+    }
+
+    nlohmann::json ga_session::get_fee_bump_options(const nlohmann::json& details) const
+    {
+        const auto& prev_tx = details.at("previous_transaction");
+        const bool can_rbf = json_get_value(prev_tx, "can_rbf", false);
+        const bool can_cpfp = json_get_value(prev_tx, "can_cpfp", false);
+
+        const amount min_fee_rate = get_min_fee_rate();
+        const auto fee_rate_p = details.find("fee_rate");
+        amount fee_rate = fee_rate_p != details.end() ? amount(*fee_rate_p) : get_default_fee_rate();
+        fee_rate = std::max(fee_rate, min_fee_rate);
+
+        const amount old_fee = amount(prev_tx.at("fee"));
+        const amount old_fee_rate = amount(prev_tx.at("fee_rate"));
+        const uint32_t vsize = prev_tx.at("transaction_vsize");
+        const auto fee_at = [](size_t size, amount rate) {
+            return amount(static_cast<amount::value_type>(std::ceil(size * rate.value() / 1000.0)));
+        };
+
+        nlohmann::json rbf;
+        if (can_rbf) {
+            // BIP125: The replacement must pay a higher fee rate, and its absolute
+            // fee must exceed the original by at least the incremental relay fee.
+            // The replacement is assumed to be the same size as the original.
+            const amount rbf_min_fee_rate = old_fee_rate + min_fee_rate;
+            const amount rbf_fee_rate = std::max(fee_rate, rbf_min_fee_rate);
+            const amount new_fee = std::max(fee_at(vsize, rbf_fee_rate), old_fee + fee_at(vsize, min_fee_rate));
+            rbf = { { "min_fee_rate", rbf_min_fee_rate.value() }, { "fee_rate", rbf_fee_rate.value() },
+                { "fee", new_fee.value() }, { "cost", (new_fee - old_fee).value() } };
+        }
+
+        nlohmann::json cpfp;
+        if (can_cpfp) {
+            // The child spends the first output paying to us, to a wallet address
+            for (const auto& output : prev_tx.at("outputs")) {
+                if (!json_get_value(output, "is_relevant", false)) {
+                    continue;
+                }
+                const std::string addr_type = output.at("address_type");
+                const size_t child_vsize = tx_vsize_from_weight(
+                    estimate_tx_weight(m_net_params, { { addr_type, 1 } }, { { "p2sh", 1 } }));
+                // The child must pay for the parent's shortfall at the target rate
+                const amount package_fee = fee_at(vsize + child_vsize, fee_rate);
+                const amount shortfall = package_fee <= old_fee ? amount() : package_fee - old_fee;
+                const amount child_fee = std::max(shortfall, fee_at(child_vsize, fee_rate));
+                if (amount(output.at("satoshi")) >= child_fee + get_dust_threshold()) {
+                    cpfp = { { "fee", child_fee.value() }, { "transaction_vsize", child_vsize },
+                        { "package_fee_rate", (old_fee + child_fee).value() * 1000 / (vsize + child_vsize) },
+                        { "cost", child_fee.value() } };
+                }
+                break;
+            }
+        }
+
+        // Prefer RBF as it replaces rather than adds a transaction, and so is cheaper
+        nlohmann::json recommended;
+        if (old_fee_rate < fee_rate) {
+            if (!rbf.is_null()) {
+                recommended = "rbf";
+            } else if (!cpfp.is_null()) {
+                recommended = "cpfp";
+            }
+        }
+        return { { "fee_rate", fee_rate.value() }, { "rbf", rbf }, { "cpfp", cpfp }, { "recommended", recommended } };
+    }
+
     amount ga_session::get_min_fee_rate() const
     {
         locker_t locker(m_mutex);
@@ -1285,8 +1750,21 @@ namespace sdk {
             } else {
                 // TODO: figure out what type is for liquid
             }
+            const std::string txhash = details.value("txhash", std::string{});
             call_notification_handler(
                 locker, new nlohmann::json({ { "event", "transaction" }, { "transaction", std::move(details) } }));
+
+            // Notify each affected subaccount that its balance has changed,
+            // so callers can refresh only the subaccounts they display
+            for (auto subaccount : affected) {
+                if (m_notification_handler == nullptr) {
+                    break; // The handler was removed while we were unlocked
+                }
+                const nlohmann::json subaccount_details = { { "pointer", subaccount },
+                    { "event_type", "balance_changed" }, { "txhash", txhash }, { "block_height", m_block_height } };
+                call_notification_handler(
+                    locker, new nlohmann::json({ { "event", "subaccount" }, { "subaccount", subaccount_details } }));
+            }
         });
     }
 
@@ -1300,6 +1778,16 @@ namespace sdk {
             if (block_height > m_block_height) {
                 m_block_height = block_height;
             }
+
+            // Remember when the block was found, to date the transactions it confirms
+            const auto key = "blocktime" + std::to_string(block_height);
+            if (!m_cache.get(key)) {
+                const auto now = std::chrono::system_clock::to_time_t(std::chrono::system_clock::now());
+                m_cache.upsert_keyvalue(key, nlohmann::json::to_msgpack(nlohmann::json(static_cast<int64_t>(now))));
+                if (m_local_encryption_key) {
+                    m_cache.save_db(m_local_encryption_key.get());
+                }
+            }
             if (m_notification_handler != nullptr) {
                 details.erase("diverged_count");
                 call_notification_handler(
@@ -1381,6 +1869,10 @@ namespace sdk {
         }
         constexpr bool watch_only = false;
         update_login_data(locker, login_data, watch_only);
+        record_event(locker, "login", { { "hw_device", !get_signer().get_hw_device().empty() } });
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
 
         const std::string receiving_id = m_login_data["receiving_id"];
         std::vector<autobahn::wamp_subscription> subscriptions;
@@ -1415,16 +1907,59 @@ namespace sdk {
         //#endif
     }
 
+    void ga_session::set_cache_encryption_key(const nlohmann::json& details)
+    {
+        const std::string key_hex = json_get_value(details, "key");
+        const std::string password = json_get_value(details, "password");
+        GDK_RUNTIME_ASSERT_MSG(!key_hex.empty() || !password.empty(), "Invalid cache encryption key");
+        const auto key = h2b(key_hex);
+        GDK_RUNTIME_ASSERT_MSG(key.empty() || key.size() == PBKDF2_HMAC_SHA512_LEN, "Invalid cache encryption key");
+
+        locker_t locker{ m_mutex };
+        // The key can't be changed once the stored data has been loaded
+        GDK_RUNTIME_ASSERT_MSG(m_local_encryption_key == boost::none, "Already logged in");
+        if (!key.empty()) {
+            auto tmp = std::array<unsigned char, PBKDF2_HMAC_SHA512_LEN>();
+            std::copy(key.begin(), key.end(), tmp.begin());
+            m_cache_encryption_key = tmp;
+        }
+        m_cache_password = password;
+    }
+
+    void ga_session::change_cache_password(const nlohmann::json& details)
+    {
+        const std::string password = json_get_value(details, "password");
+
+        locker_t locker{ m_mutex };
+        GDK_RUNTIME_ASSERT_MSG(m_local_encryption_key && m_cache_base_key, "Not logged in");
+        const auto new_key = get_cache_key(m_cache_base_key.get(), password);
+        // Re-encrypt the stored data under the new key, removing the old file. The
+        // new key is only used once that succeeds, so the data is never stranded
+        GDK_RUNTIME_ASSERT_MSG(
+            m_cache.rekey_db(m_local_encryption_key.get(), new_key), "Failed to re-encrypt the cache");
+        m_cache.set_encryption_key_check(m_cache_base_key.get(), new_key);
+        m_cache_password = password;
+        m_local_encryption_key = new_key;
+    }
+
+    void ga_session::load_cache(locker_t& locker, uint32_t type)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        const auto key = get_cache_key(m_cache_base_key.get(), m_cache_password);
+        GDK_RUNTIME_ASSERT_MSG(
+            m_cache.check_encryption_key(m_cache_base_key.get(), key, type), "Invalid cache password");
+        m_local_encryption_key = key;
+        m_cache.load_db(m_local_encryption_key.get(), type);
+        m_cache.clear_keyvalue(CACHE_UPCOMING_NLOCKTIME);
+    }
+
     void ga_session::set_local_encryption_key(byte_span_t key)
     {
         locker_t locker{ m_mutex };
         GDK_RUNTIME_ASSERT(key.size() == PBKDF2_HMAC_SHA512_LEN);
         GDK_RUNTIME_ASSERT(m_local_encryption_key == boost::none);
-        auto tmp = std::array<unsigned char, PBKDF2_HMAC_SHA512_LEN>();
-        std::copy(key.begin(), key.end(), tmp.begin());
-        m_local_encryption_key = tmp;
-        m_cache.load_db(m_local_encryption_key.get(), /*hw*/ 1);
-        m_cache.clear_keyvalue(CACHE_UPCOMING_NLOCKTIME);
+        m_cache_base_key = get_cache_base_key(m_cache_encryption_key, key);
+        load_cache(locker, /*hw*/ 1);
     }
 
     void ga_session::on_failed_login()
@@ -1435,6 +1970,7 @@ namespace sdk {
             m_user_pubkeys.reset();
             m_mnemonic.clear();
             m_local_encryption_key = boost::none;
+            m_cache_base_key = boost::none;
         } catch (const std::exception& ex) {
         }
     }
@@ -1464,14 +2000,9 @@ namespace sdk {
         // Cache local encryption password
         const auto pwd_xpub = get_signer().get_xpub(PASSWORD_PATH);
 
-        m_local_encryption_key = [&pwd_xpub] {
-            const auto local_password = pbkdf2_hmac_sha512(pwd_xpub.second, PASSWORD_SALT);
-            std::array<unsigned char, PBKDF2_HMAC_SHA512_LEN> tmp;
-            std::copy(local_password.begin(), local_password.end(), tmp.begin());
-            return boost::optional<std::array<unsigned char, PBKDF2_HMAC_SHA512_LEN>>(tmp);
-        }();
-        m_cache.load_db(m_local_encryption_key.get(), /*sw*/ 0);
-        m_cache.clear_keyvalue(CACHE_UPCOMING_NLOCKTIME);
+        const auto local_password = pbkdf2_hmac_sha512(pwd_xpub.second, PASSWORD_SALT);
+        m_cache_base_key = get_cache_base_key(m_cache_encryption_key, local_password);
+        load_cache(locker, /*sw*/ 0);
 
         // TODO: Unify normal and trezor logins
         std::string challenge;
@@ -1546,6 +2077,10 @@ namespace sdk {
 
         cleanup_appearance_settings(locker, appearance);
         push_appearance_to_server(locker);
+        record_event(locker, "settings_changed", { { "settings", settings } });
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
 
         const auto pricing_p = settings.find("pricing");
         if (pricing_p != settings.end()) {
@@ -1599,7 +2134,20 @@ namespace sdk {
     void ga_session::register_subaccount_xpubs(const std::vector<std::string>& bip32_xpubs)
     {
         locker_t locker(m_mutex);
+        register_subaccount_xpubs(locker, bip32_xpubs);
+
+        // Snapshot the xpubs so that future logins can skip fetching them
+        nlohmann::json snapshot = { { "xpubs", nlohmann::json::object() } };
+        for (size_t i = 0; i < m_subaccounts.size(); ++i) {
+            const uint32_t pointer = m_subaccounts[i]["pointer"];
+            snapshot["xpubs"][std::to_string(pointer)] = bip32_xpubs[i];
+        }
+        save_session_snapshot(locker, snapshot);
+    }
 
+    void ga_session::register_subaccount_xpubs(locker_t& locker, const std::vector<std::string>& bip32_xpubs)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
         GDK_RUNTIME_ASSERT(!m_subaccounts.empty());
         GDK_RUNTIME_ASSERT(bip32_xpubs.size() == m_subaccounts.size());
         GDK_RUNTIME_ASSERT(!m_user_pubkeys);
@@ -1610,12 +2158,89 @@ namespace sdk {
         }
     }
 
+    // Register the subaccount xpubs from a previous login's snapshot, if it
+    // is still valid for the wallet. Returns false if the caller must fetch
+    // the xpubs from the signer instead.
+    bool ga_session::resume_from_snapshot(const std::string& master_xpub)
+    {
+        locker_t locker(m_mutex);
+
+        const auto snapshot = get_session_snapshot(locker);
+        if (snapshot.empty()) {
+            return false;
+        }
+        const auto& xpubs = snapshot.at("xpubs");
+        std::vector<std::string> bip32_xpubs;
+        for (size_t i = 0; i < m_subaccounts.size(); ++i) {
+            const uint32_t pointer = m_subaccounts[i]["pointer"];
+            const auto p = xpubs.find(std::to_string(pointer));
+            if (p == xpubs.end()) {
+                // A subaccount has been created since the snapshot was taken
+                GDK_LOG_SEV(log_level::info) << "session snapshot is stale";
+                return false;
+            }
+            bip32_xpubs.emplace_back(p.value());
+        }
+        if (bip32_xpubs.empty() || bip32_xpubs.front() != master_xpub) {
+            // The snapshot doesn't belong to the wallet being logged in to
+            GDK_LOG_SEV(log_level::warning) << "session snapshot does not match wallet";
+            return false;
+        }
+        register_subaccount_xpubs(locker, bip32_xpubs);
+        return true;
+    }
+
+    nlohmann::json ga_session::get_session_snapshot(locker_t& locker)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        if (!m_local_encryption_key) {
+            return nlohmann::json();
+        }
+        const auto value = m_cache.get("sessionsnapshot");
+        if (!value) {
+            return nlohmann::json();
+        }
+        return nlohmann::json::from_msgpack(value->begin(), value->end());
+    }
+
+    void ga_session::save_session_snapshot(locker_t& locker, const nlohmann::json& snapshot)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        if (m_local_encryption_key) {
+            m_cache.upsert_keyvalue("sessionsnapshot", nlohmann::json::to_msgpack(snapshot));
+            m_cache.save_db(m_local_encryption_key.get());
+        }
+    }
+
     nlohmann::json ga_session::get_fee_estimates()
     {
         locker_t locker(m_mutex);
 
-        // TODO: augment with last_updated, user preference for display?
-        return { { "fees", m_fee_estimates } };
+        // TODO: augment with user preference for display?
+        const uint64_t age = get_fee_estimates_age(locker);
+        nlohmann::json result = { { "fees", m_fee_estimates }, { "is_stale", age > DEFAULT_MAX_FEE_ESTIMATES_AGE } };
+        if (m_fee_estimates_updated != 0) {
+            result["last_updated"] = m_fee_estimates_updated;
+        }
+        return result;
+    }
+
+    bool ga_session::is_fee_estimates_stale(const nlohmann::json& details) const
+    {
+        const uint64_t max_age = json_get_value(details, "max_fee_estimates_age", DEFAULT_MAX_FEE_ESTIMATES_AGE);
+        locker_t locker(m_mutex);
+        return get_fee_estimates_age(locker) > max_age;
+    }
+
+    uint64_t ga_session::get_fee_estimates_age(locker_t& locker) const
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        if (m_fee_estimates_updated == 0) {
+            return std::numeric_limits<uint64_t>::max(); // Never updated
+        }
+        const auto now = std::chrono::system_clock::now().time_since_epoch();
+        const uint64_t now_secs = std::chrono::duration_cast<std::chrono::seconds>(now).count();
+        return now_secs > m_fee_estimates_updated ? now_secs - m_fee_estimates_updated : 0;
     }
 
     std::string ga_session::get_mnemonic_passphrase(const std::string& password)
@@ -1667,6 +2292,69 @@ namespace sdk {
         return std::make_pair(message_hash_hex, path);
     }
 
+    nlohmann::json ga_session::get_message_signing_details(const nlohmann::json& details)
+    {
+        const uint32_t subaccount = details.at("subaccount");
+        const uint32_t pointer = details.at("pointer");
+        const std::string message = details.at("message");
+        // Only addresses the wallet has generated can be signed for
+        const auto address = get_previous_address(subaccount, pointer);
+
+        locker_t locker(m_mutex);
+        const auto public_key = get_user_pubkeys().derive(subaccount, pointer);
+        return { { "address", address.at("address") }, { "message", message },
+            { "path", ga_user_pubkeys::get_full_path(subaccount, pointer) }, { "public_key", b2h(public_key) } };
+    }
+
+    // Sign a message, or with "hw_signature" given, encode the DER signature returned by a hardware wallet
+    nlohmann::json ga_session::sign_message(const nlohmann::json& details)
+    {
+        nlohmann::json result = get_message_signing_details(details);
+        const std::vector<uint32_t> path = result.at("path");
+        const std::string message = result.at("message");
+        const auto hash = format_bitcoin_message_hash(ustring_span(message));
+
+        ecdsa_sig_t sig;
+        const auto hw_signature_p = details.find("hw_signature");
+        if (hw_signature_p != details.end()) {
+            sig = ec_sig_from_der(h2b(*hw_signature_p));
+        } else {
+            locker_t locker(m_mutex);
+            sig = get_signer().sign_hash(path, hash);
+        }
+        result["signature"] = get_message_signature(hash, h2b(result.at("public_key")), sig);
+        return result;
+    }
+
+    bool ga_session::verify_message(const nlohmann::json& details)
+    {
+        const std::string address = details.at("address");
+        const std::string message = details.at("message");
+        const auto hash = format_bitcoin_message_hash(ustring_span(message));
+        std::vector<unsigned char> public_key;
+        try {
+            public_key = get_message_signature_public_key(hash, details.at("signature"));
+        } catch (const std::exception&) {
+            return false; // Malformed signature
+        }
+        if (is_public_key_address(m_net_params, address, public_key)) {
+            return true;
+        }
+
+        // Wallet addresses are multisig, so instead require the signer to be
+        // the user's key in the script of one of our own addresses
+        locker_t locker(m_mutex);
+        for (const auto& sa : m_subaccounts) {
+            for (const auto& generated : m_cache.get_generated_addresses(sa.first)) {
+                if (generated.at("address") == address) {
+                    const auto user_key = get_user_pubkeys().derive(sa.first, generated.at("pointer"));
+                    return std::equal(user_key.begin(), user_key.end(), public_key.begin(), public_key.end());
+                }
+            }
+        }
+        return false;
+    }
+
     void ga_session::ack_system_message(const std::string& message)
     {
         const auto info = get_system_message_info(message);
@@ -1712,6 +2400,69 @@ namespace sdk {
         return amount::convert(amount_json, m_fiat_currency, m_fiat_rate);
     }
 
+    nlohmann::json ga_session::parse_amount(const nlohmann::json& amount_details) const
+    {
+        const auto amount_json = amount::parse(amount_details.at("amount"), amount_details.value("unit", "btc"),
+            amount_details.value("decimal_separator", std::string{}));
+        locker_t locker(m_mutex);
+        return convert_amount(locker, amount_json);
+    }
+
+    nlohmann::json ga_session::parse_address(const nlohmann::json& details) const
+    {
+        return ga::sdk::parse_address(m_net_params, details.at("address"));
+    }
+
+    nlohmann::json ga_session::parse_uri(const nlohmann::json& details) const
+    {
+        const std::string uri = details.at("uri");
+        const auto parsed = parse_bitcoin_uri(uri, m_net_params.bip21_prefix());
+        const std::string address = parsed.is_null() ? uri : json_get_value(parsed, "address");
+        scriptpubkey_from_address(m_net_params, address); // Throws if the address is invalid
+
+        nlohmann::json result = { { "address", address } };
+        if (parsed.is_null()) {
+            return result; // A plain address
+        }
+        const auto& params = parsed.at("bip21-params");
+        result["bip21-params"] = params;
+        for (const auto& key : { "label", "message" }) {
+            if (params.contains(key)) {
+                result[key] = params[key];
+            }
+        }
+
+        std::string asset_tag = "btc";
+        if (params.contains("assetid")) {
+            const std::string asset_id = params["assetid"];
+            bool valid = m_net_params.liquid() && asset_id.size() == ASSET_TAG_LEN * 2;
+            try {
+                valid = valid && h2b(asset_id).size() == ASSET_TAG_LEN;
+            } catch (const std::exception&) {
+                valid = false;
+            }
+            if (!valid) {
+                throw user_error(res::id_invalid_payment_request_assetid);
+            }
+            asset_tag = asset_id == m_net_params.policy_asset() ? "btc" : asset_id;
+            result["asset_tag"] = asset_tag;
+        }
+
+        if (params.contains("amount")) {
+            if (m_net_params.liquid() && !params.contains("assetid")) {
+                throw user_error(res::id_invalid_payment_request_assetid);
+            }
+            // Liquid asset amounts are given in the consensus form, i.e. as if they had 8 decimal places
+            const nlohmann::json uri_amount = { { "btc", params["amount"].get<std::string>() } };
+            const auto converted = convert_amount(uri_amount);
+            result["satoshi"] = converted.at("satoshi");
+            if (asset_tag == "btc") {
+                result["amount"] = converted;
+            }
+        }
+        return result;
+    }
+
     nlohmann::json ga_session::convert_fiat_cents(ga_session::locker_t& locker, amount::value_type fiat_cents) const
     {
         GDK_RUNTIME_ASSERT(locker.owns_lock());
@@ -1794,11 +2545,18 @@ namespace sdk {
         nlohmann::json balance({ { "btc", 0 } });
         for (const auto& item : utxos.items()) {
             const auto& key = item.key();
+            if (key == "suspicious") {
+                continue; // Added to their assets below
+            }
             const auto& item_utxos = item.value();
             const int64_t satoshi
                 = accumulate_if(item_utxos, [](auto utxo) { return utxo.find("error") == utxo.end(); });
             balance[key] = satoshi;
         }
+        for (const auto& utxo : utxos.value("suspicious", nlohmann::json::array())) {
+            const auto asset_tag = asset_id_from_string(utxo.value("asset_id", std::string{}));
+            balance[asset_tag] = json_get_value(balance, asset_tag, int64_t{ 0 }) + utxo.at("satoshi").get<int64_t>();
+        }
 
         return balance;
     }
@@ -1838,9 +2596,40 @@ namespace sdk {
             }
         }
 
+        details["hidden"] = get_hidden_subaccounts(locker).count(subaccount) != 0;
         return details;
     }
 
+    std::set<uint32_t> ga_session::get_hidden_subaccounts(ga_session::locker_t& locker)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        const auto value = m_cache.get("hiddensubaccounts");
+        if (!value) {
+            return std::set<uint32_t>();
+        }
+        return nlohmann::json::from_msgpack(value->begin(), value->end()).get<std::set<uint32_t>>();
+    }
+
+    void ga_session::set_subaccount_hidden(uint32_t subaccount, bool hidden)
+    {
+        GDK_RUNTIME_ASSERT_MSG(subaccount != 0, "Main subaccount cannot be hidden");
+
+        locker_t locker(m_mutex);
+        GDK_RUNTIME_ASSERT_MSG(m_subaccounts.find(subaccount) != m_subaccounts.end(), "Unknown subaccount");
+        auto hidden_subaccounts = get_hidden_subaccounts(locker);
+        if (hidden) {
+            hidden_subaccounts.insert(subaccount);
+        } else {
+            hidden_subaccounts.erase(subaccount);
+        }
+        const nlohmann::json stored = hidden_subaccounts;
+        m_cache.upsert_keyvalue("hiddensubaccounts", nlohmann::json::to_msgpack(stored));
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
+        m_subaccounts[subaccount]["hidden"] = hidden;
+    }
+
     void ga_session::rename_subaccount(uint32_t subaccount, const std::string& new_name)
     {
         GDK_RUNTIME_ASSERT_MSG(subaccount != 0, "Main subaccount name cannot be changed");
@@ -1954,6 +2743,11 @@ namespace sdk {
         locker_t locker(m_mutex);
         constexpr bool has_txs = false;
         m_user_pubkeys->add_subaccount(subaccount, make_xpub(xpub));
+        auto snapshot = get_session_snapshot(locker);
+        if (!snapshot.empty()) {
+            snapshot["xpubs"][std::to_string(subaccount)] = xpub;
+            save_session_snapshot(locker, snapshot);
+        }
         nlohmann::json subaccount_details = insert_subaccount(
             locker, subaccount, name, receiving_id, recovery_pub_key, recovery_chain_code, type, amount(), has_txs, 0);
 
@@ -1979,6 +2773,10 @@ namespace sdk {
         change_settings("tx_limits", as_messagepack(details).get(), twofactor_data);
         locker_t locker(m_mutex);
         update_spending_limits(locker, details);
+        record_event(locker, "settings_changed", { { "settings", { { "limits", details } } } });
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
     }
 
     void ga_session::change_settings_pricing_source(const std::string& currency, const std::string& exchange)
@@ -2031,6 +2829,18 @@ namespace sdk {
                 utxo["confidential"] = true;
                 return;
             }
+            // Don't retry outputs that previously failed to unblind with our
+            // keys, unless their blinders have since been imported
+            if (m_cache.get(get_unblind_failure_key(utxo))) {
+                locker.unlock();
+                if (unblind_utxo_with_imported_blinders(utxo)) {
+                    add_sync_progress("outputs_unblinded", 1);
+                } else {
+                    utxo["error"] = "failed to unblind utxo";
+                    add_sync_progress("outputs_unblind_failed", 1);
+                }
+                return;
+            }
         }
         const auto rangeproof = h2b(utxo.at("range_proof"));
         const auto commitment = h2b(utxo.at("commitment"));
@@ -2060,9 +2870,13 @@ namespace sdk {
                 const auto blinding_nonce = get_blinding_nonce(utxo.at("nonce_commitment"), utxo.at("script"));
                 unblinded
                     = asset_unblind_with_nonce(blinding_nonce, rangeproof, commitment, extra_commitment, asset_tag);
+            } else if (unblind_utxo_with_imported_blinders(utxo)) {
+                add_sync_progress("outputs_unblinded", 1);
+                return;
             } else {
                 // hw and missing nonce in the map
                 utxo["error"] = "missing blinding nonce";
+                add_sync_progress("outputs_unblind_failed", 1);
                 return;
             }
 
@@ -2081,13 +2895,78 @@ namespace sdk {
                     m_cache.insert_liquidoutput(txhash, vout, utxo);
                 }
             }
+            add_sync_progress("outputs_unblinded", 1);
         } catch (const std::exception& ex) {
+            // Not blinded to our key: the user may have imported its blinders
+            if (unblind_utxo_with_imported_blinders(utxo)) {
+                add_sync_progress("outputs_unblinded", 1);
+                return;
+            }
             utxo["error"] = "failed to unblind utxo";
+            add_sync_progress("outputs_unblind_failed", 1);
+            if (utxo.contains("txhash")) {
+                locker_t locker(m_mutex);
+                m_cache.upsert_keyvalue(get_unblind_failure_key(utxo), nlohmann::json::to_msgpack(true));
+            }
         }
     }
 
+    void ga_session::unblind_utxos(const std::vector<nlohmann::json*>& utxos, const std::string& policy_asset)
+    {
+        // Unblinding is CPU bound, so spread larger batches across threads.
+        // unblind_utxo only takes the session lock briefly to access the cache
+        constexpr size_t min_parallel_utxos = 8;
+        if (utxos.size() < min_parallel_utxos || std::thread::hardware_concurrency() < 2) {
+            for (auto utxo : utxos) {
+                unblind_utxo(*utxo, policy_asset);
+            }
+            return;
+        }
+
+        std::vector<std::future<void>> results;
+        results.reserve(utxos.size());
+        for (auto utxo : utxos) {
+            auto promise = std::make_shared<std::promise<void>>();
+            results.emplace_back(promise->get_future());
+            boost::asio::post(m_unblind_pool, [this, utxo, promise, &policy_asset] {
+                try {
+                    unblind_utxo(*utxo, policy_asset);
+                    promise->set_value();
+                } catch (const std::exception&) {
+                    promise->set_exception(std::current_exception());
+                }
+            });
+        }
+        // Wait for every task before rethrowing any error, as they refer to the utxos
+        for (const auto& result : results) {
+            result.wait();
+        }
+        for (auto& result : results) {
+            result.get();
+        }
+    }
+
+    void ga_session::add_sync_progress(const std::string& counter, uint32_t n)
+    {
+        locker_t locker(m_mutex);
+        m_sync_status[counter] = json_get_value(m_sync_status, counter, 0u) + n;
+    }
+
+    nlohmann::json ga_session::get_sync_status()
+    {
+        locker_t locker(m_mutex);
+        nlohmann::json status = { { "pages_fetched", 0 }, { "transactions_fetched", 0 }, { "outputs_unblinded", 0 },
+            { "outputs_unblind_failed", 0 } };
+        if (m_sync_status.is_object()) {
+            status.update(m_sync_status);
+        }
+        status["block_height"] = m_block_height;
+        return status;
+    }
+
     nlohmann::json ga_session::cleanup_utxos(nlohmann::json& utxos, const std::string& policy_asset)
     {
+        std::vector<nlohmann::json*> blinded;
         for (auto& utxo : utxos) {
             // Clean up the type of returned values
             const bool external = !json_get_value(utxo, "private_key").empty();
@@ -2126,7 +3005,7 @@ namespace sdk {
                 // TODO: check data returned by server for blinded utxos
                 if (!policy_asset.empty()) {
                     if (json_get_value(utxo, "is_relevant", true)) {
-                        unblind_utxo(utxo, policy_asset);
+                        blinded.push_back(&utxo);
                     }
                 } else {
                     amount::value_type value;
@@ -2134,6 +3013,11 @@ namespace sdk {
                     utxo["satoshi"] = value;
                 }
             }
+        }
+
+        unblind_utxos(blinded, policy_asset);
+
+        for (auto& utxo : utxos) {
             utxo.erase("value");
             if (utxo.find("block_height") != utxo.end() && utxo["block_height"].is_null()) {
                 utxo["block_height"] = 0;
@@ -2153,10 +3037,543 @@ namespace sdk {
         const uint32_t subaccount = details.at("subaccount");
         const uint32_t first = details.at("first");
         const uint32_t count = details.at("count");
+        const std::string from = json_get_value(details, "from");
+        const std::string to = json_get_value(details, "to");
+        const uint32_t cancel_id = m_cancel_id;
 
-        return m_tx_list_caches.get(subaccount)->get(first, count, [this, subaccount](uint32_t page) {
+        auto tx_list_cache = m_tx_list_caches.get(subaccount);
+        const auto get_page = [this, subaccount, cancel_id](uint32_t page) {
+            check_cancelled(cancel_id);
             return get_transactions(subaccount, page);
+        };
+        if (from.empty() && to.empty()) {
+            auto txs = tx_list_cache->get(first, count, get_page);
+            add_local_details(subaccount, txs);
+            return txs;
+        }
+
+        // Transactions are listed newest first, so fetch them until we
+        // pass the start of the date range, returning 'count' txs from
+        // position 'first' within the range.
+        // TODO: Use the block header timestamp rather than the time the tx was first seen
+        constexpr uint32_t page_size = 30;
+        std::vector<nlohmann::json> result;
+        uint32_t skipped = 0;
+        for (uint32_t page_first = 0; result.size() < count; page_first += page_size) {
+            const auto txs = tx_list_cache->get(page_first, page_size, get_page);
+            for (const auto& tx : txs) {
+                const std::string date = tx.at("created_at");
+                if (!to.empty() && date.compare(0, to.size(), to) > 0) {
+                    continue; // After the range
+                }
+                if (!from.empty() && date.compare(0, from.size(), from) < 0) {
+                    add_local_details(subaccount, result);
+                    return result; // Before the range
+                }
+                if (skipped < first) {
+                    ++skipped;
+                } else if (result.size() < count) {
+                    result.emplace_back(tx);
+                }
+            }
+            if (txs.size() < page_size) {
+                break;
+            }
+        }
+        add_local_details(subaccount, result);
+        return result;
+    }
+
+    nlohmann::json ga_session::get_balance_history(const nlohmann::json& details)
+    {
+        const uint32_t subaccount = details.at("subaccount");
+        const std::string from = json_get_value(details, "from");
+        const std::string to = json_get_value(details, "to");
+        const std::string granularity = details.value("granularity", "day");
+
+        // Periods are prefixes of the "YYYY-MM-DD" date
+        const size_t period_len
+            = granularity == "day" ? 10 : granularity == "month" ? 7 : granularity == "year" ? 4 : 0;
+        GDK_RUNTIME_ASSERT_MSG(period_len != 0, "unknown granularity");
+
+        std::map<std::string, int64_t> balance;
+        nlohmann::json opening_balance = nlohmann::json::object();
+        nlohmann::json history = nlohmann::json::array();
+        for (const auto& day : get_daily_balance_changes(subaccount).items()) {
+            const std::string& date = day.key();
+            if (!to.empty() && date.compare(0, to.size(), to) > 0) {
+                break;
+            }
+            for (const auto& change : day.value().items()) {
+                balance[change.key()] += change.value().get<int64_t>();
+            }
+            if (!from.empty() && date.compare(0, from.size(), from) < 0) {
+                opening_balance = balance;
+                continue;
+            }
+            // Each entry holds the balance at the end of its period
+            const std::string period = date.substr(0, period_len);
+            if (!history.empty() && history.back()["date"] == period) {
+                history.back()["satoshi"] = balance;
+            } else {
+                history.push_back({ { "date", period }, { "satoshi", balance } });
+            }
+        }
+        return { { "subaccount", subaccount }, { "granularity", granularity }, { "opening_balance", opening_balance },
+            { "history", history } };
+    }
+
+    nlohmann::json ga_session::get_spending_summary(const nlohmann::json& details)
+    {
+        const uint32_t subaccount = details.at("subaccount");
+        const std::string from = json_get_value(details, "from");
+        const std::string to = json_get_value(details, "to");
+
+        // Totals are cached until the next block, as only confirmed transactions are included
+        const std::string cache_key = "spendingsummary" + std::to_string(subaccount);
+        const uint32_t block_height = get_block_height();
+        nlohmann::json months;
+        {
+            locker_t locker(m_mutex);
+            const auto value = m_cache.get(cache_key);
+            if (value) {
+                nlohmann::json cached = nlohmann::json::from_msgpack(value->begin(), value->end());
+                if (cached.at("block_height") == block_height) {
+                    months = cached["months"];
+                }
+            }
+        }
+
+        if (months.is_null()) {
+            months = nlohmann::json::object();
+            for_each_transaction(subaccount, [this, &months](const nlohmann::json& tx) {
+                const uint32_t tx_block_height = json_get_value(tx, "block_height", 0u);
+                if (tx_block_height == 0) {
+                    return; // Unconfirmed
+                }
+                auto& month = months[get_block_date(tx_block_height, tx.at("created_at")).substr(0, 7)];
+                // The wallet pays the fee of any tx spending its coins
+                const int64_t fee = spends_wallet_coins(tx) ? tx.at("fee").get<int64_t>() : 0;
+                for (const auto& change : get_balance_changes(tx)) {
+                    auto& totals = month[change.first];
+                    if (totals.is_null()) {
+                        totals = { { "incoming", 0 }, { "outgoing", 0 }, { "fee", 0 } };
+                    }
+                    int64_t satoshi = change.second;
+                    if (fee != 0 && change.first == "btc") {
+                        // The amount spent includes the fee, which is reported separately
+                        if (satoshi < 0) {
+                            satoshi = std::min<int64_t>(satoshi + fee, 0);
+                        }
+                        totals["fee"] = totals["fee"].get<int64_t>() + fee;
+                    }
+                    const char* key = satoshi < 0 ? "outgoing" : "incoming";
+                    totals[key] = totals[key].get<int64_t>() + std::abs(satoshi);
+                }
+            });
+
+            locker_t locker(m_mutex);
+            const nlohmann::json cached = { { "block_height", block_height }, { "months", months } };
+            m_cache.upsert_keyvalue(cache_key, nlohmann::json::to_msgpack(cached));
+            if (m_local_encryption_key) {
+                m_cache.save_db(m_local_encryption_key.get());
+            }
+        }
+
+        nlohmann::json result = nlohmann::json::object();
+        for (const auto& month : months.items()) {
+            if ((from.empty() || month.key().compare(0, from.size(), from) >= 0)
+                && (to.empty() || month.key().compare(0, to.size(), to) <= 0)) {
+                result[month.key()] = month.value();
+            }
+        }
+        return { { "subaccount", subaccount }, { "months", result } };
+    }
+
+    nlohmann::json ga_session::get_daily_balance_changes(uint32_t subaccount)
+    {
+        // Changes are cached until the next block, as only confirmed transactions are included
+        const std::string cache_key = "balancehistory" + std::to_string(subaccount);
+        const uint32_t block_height = get_block_height();
+        {
+            locker_t locker(m_mutex);
+            const auto value = m_cache.get(cache_key);
+            if (value) {
+                nlohmann::json cached = nlohmann::json::from_msgpack(value->begin(), value->end());
+                if (cached.at("block_height") == block_height) {
+                    return cached["changes"];
+                }
+            }
+        }
+
+        nlohmann::json changes = nlohmann::json::object();
+        for_each_transaction(subaccount, [this, &changes](const nlohmann::json& tx) {
+            const uint32_t tx_block_height = json_get_value(tx, "block_height", 0u);
+            if (tx_block_height == 0) {
+                return; // Unconfirmed
+            }
+            auto& day_changes = changes[get_block_date(tx_block_height, tx.at("created_at"))];
+            for (const auto& change : get_balance_changes(tx)) {
+                const int64_t total = json_get_value(day_changes, change.first, int64_t{ 0 });
+                day_changes[change.first] = total + change.second;
+            }
         });
+
+        locker_t locker(m_mutex);
+        const nlohmann::json cached = { { "block_height", block_height }, { "changes", changes } };
+        m_cache.upsert_keyvalue(cache_key, nlohmann::json::to_msgpack(cached));
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
+        return changes;
+    }
+
+    // Return the "YYYY-MM-DD" date of a block. The server doesn't give block
+    // timestamps, so the time each block was announced to us is recorded.
+    // Blocks found before that fall back to the time the tx was first seen
+    std::string ga_session::get_block_date(uint32_t block_height, const std::string& created_at)
+    {
+        locker_t locker(m_mutex);
+        const auto value = m_cache.get("blocktime" + std::to_string(block_height));
+        if (!value) {
+            return created_at.substr(0, 10);
+        }
+        const std::time_t block_time = nlohmann::json::from_msgpack(value->begin(), value->end()).get<int64_t>();
+        std::array<char, 11> date;
+        const auto written = std::strftime(date.data(), date.size(), "%Y-%m-%d", std::gmtime(&block_time));
+        GDK_RUNTIME_ASSERT(written != 0);
+        return date.data();
+    }
+
+    void ga_session::for_each_transaction(uint32_t subaccount, const std::function<void(const nlohmann::json&)>& fn)
+    {
+        constexpr uint32_t page_size = 30;
+        const uint32_t cancel_id = m_cancel_id;
+        for (uint32_t first = 0;; first += page_size) {
+            check_cancelled(cancel_id);
+            const auto txs
+                = get_transactions({ { "subaccount", subaccount }, { "first", first }, { "count", page_size } });
+            for (const auto& tx : txs) {
+                fn(tx);
+            }
+            if (txs.size() < page_size) {
+                break;
+            }
+        }
+    }
+
+    // Call fn for each transaction that is unconfirmed or confirmed above examined_height,
+    // returning the highest block height seen. Unconfirmed transactions are few, so they
+    // are always included. Transactions are listed newest first, so the walk stops at the
+    // first page holding one that was already examined
+    uint32_t ga_session::for_each_new_transaction(
+        uint32_t subaccount, uint32_t examined_height, const std::function<void(const nlohmann::json&)>& fn)
+    {
+        constexpr uint32_t page_size = 30;
+        const uint32_t cancel_id = m_cancel_id;
+        uint32_t highest_height = examined_height;
+        for (uint32_t first = 0;; first += page_size) {
+            check_cancelled(cancel_id);
+            const auto txs
+                = get_transactions({ { "subaccount", subaccount }, { "first", first }, { "count", page_size } });
+            bool have_examined = false;
+            for (const auto& tx : txs) {
+                const uint32_t tx_block_height = json_get_value(tx, "block_height", 0u);
+                if (tx_block_height != 0 && tx_block_height <= examined_height) {
+                    have_examined = true;
+                    continue;
+                }
+                highest_height = std::max(highest_height, tx_block_height);
+                fn(tx);
+            }
+            if (have_examined || txs.size() < page_size) {
+                return highest_height;
+            }
+        }
+    }
+
+    nlohmann::json ga_session::get_generated_addresses(const nlohmann::json& details)
+    {
+        const uint32_t subaccount = details.at("subaccount");
+        nlohmann::json addresses;
+        {
+            locker_t locker(m_mutex);
+            addresses = m_cache.get_generated_addresses(subaccount);
+        }
+
+        // Addresses unused for this many days can be suggested for archiving
+        const uint32_t inactive_days = json_get_value(details, "inactive_days", 0u);
+        const auto now = std::chrono::system_clock::now();
+        const auto inactive_time
+            = std::chrono::system_clock::to_time_t(now - std::chrono::hours(24 * inactive_days));
+        const std::tm* inactive_tm = std::gmtime(&inactive_time);
+        std::array<char, 20> inactive_date;
+        const auto written
+            = std::strftime(inactive_date.data(), inactive_date.size(), "%Y-%m-%d %H:%M:%S", inactive_tm);
+        GDK_RUNTIME_ASSERT(written != 0);
+
+        const auto activity = get_address_activity(subaccount);
+        for (auto& address : addresses) {
+            const auto p = activity.find(address.at("pointer"));
+            const bool has_received = p != activity.end();
+            address["has_received"] = has_received;
+            if (has_received) {
+                address["first_seen"] = p->second.at("first_seen");
+                address["last_active"] = p->second.at("last_active");
+            }
+            if (inactive_days != 0) {
+                const bool is_inactive = has_received
+                    ? p->second.at("last_active").get<std::string>() < inactive_date.data()
+                    : address.at("created_at").get<int64_t>() < static_cast<int64_t>(inactive_time);
+                address["is_inactive"] = is_inactive;
+            }
+        }
+        return { { "addresses", addresses } };
+    }
+
+    // Returns the first and last times each address pointer in a subaccount appeared
+    // in a transaction. This is persisted so that addresses remain known as used even
+    // if the transactions using them are later replaced, and so that only transactions
+    // newer than those already examined need to be looked at
+    std::map<uint32_t, nlohmann::json> ga_session::get_address_activity(uint32_t subaccount)
+    {
+        const std::string cache_key = "addressactivity" + std::to_string(subaccount);
+        const std::string height_key = "addressactivityheight" + std::to_string(subaccount);
+        std::map<uint32_t, nlohmann::json> activity;
+        uint32_t examined_height = 0;
+        {
+            locker_t locker(m_mutex);
+            const auto value = m_cache.get(cache_key);
+            if (value) {
+                const auto cached = nlohmann::json::from_msgpack(value->begin(), value->end());
+                for (const auto& item : cached.items()) {
+                    activity.emplace(std::stoul(item.key()), item.value());
+                }
+            }
+            const auto height = m_cache.get(height_key);
+            if (height) {
+                examined_height = nlohmann::json::from_msgpack(height->begin(), height->end());
+            }
+        }
+
+        bool changed = false;
+        const auto add_activity = [subaccount, &activity, &changed](const nlohmann::json& ep, const std::string& date) {
+            if (!json_get_value(ep, "is_relevant", false) || ep.at("subaccount") != subaccount) {
+                return;
+            }
+            auto& times = activity[ep.at("pointer").get<uint32_t>()];
+            if (times.is_null()) {
+                times = { { "first_seen", date }, { "last_active", date } };
+                changed = true;
+            } else if (date < times.at("first_seen").get<std::string>()) {
+                times["first_seen"] = date;
+                changed = true;
+            } else if (date > times.at("last_active").get<std::string>()) {
+                times["last_active"] = date;
+                changed = true;
+            }
+        };
+
+        // Only transactions not already examined are looked at
+        const uint32_t highest_height = for_each_new_transaction(
+            subaccount, examined_height, [&add_activity](const nlohmann::json& tx) {
+                const std::string date = tx.at("created_at");
+                for (const auto& ep : tx.at("inputs")) {
+                    add_activity(ep, date);
+                }
+                for (const auto& ep : tx.at("outputs")) {
+                    add_activity(ep, date);
+                }
+                // Replaced transactions may have paid to different addresses
+                for (const auto& replaced : tx.value("replaces", nlohmann::json::array())) {
+                    for (const auto& ep : replaced.at("outputs")) {
+                        add_activity(ep, date);
+                    }
+                }
+            });
+
+        if (!changed && highest_height == examined_height) {
+            return activity;
+        }
+        nlohmann::json cached = nlohmann::json::object();
+        for (const auto& item : activity) {
+            cached[std::to_string(item.first)] = item.second;
+        }
+        locker_t locker(m_mutex);
+        m_cache.upsert_keyvalue(cache_key, nlohmann::json::to_msgpack(cached));
+        m_cache.upsert_keyvalue(height_key, nlohmann::json::to_msgpack(nlohmann::json(highest_height)));
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
+        return activity;
+    }
+
+    nlohmann::json ga_session::rescan_transactions(const nlohmann::json& details)
+    {
+        std::vector<uint32_t> subaccounts;
+        {
+            locker_t locker(m_mutex);
+            if (details.contains("subaccount")) {
+                const uint32_t subaccount = details["subaccount"];
+                GDK_RUNTIME_ASSERT_MSG(m_subaccounts.find(subaccount) != m_subaccounts.end(), "Unknown subaccount");
+                subaccounts.push_back(subaccount);
+            } else {
+                for (const auto& sa : m_subaccounts) {
+                    subaccounts.push_back(sa.first);
+                }
+            }
+
+            // Discard everything derived from the transaction history. User
+            // data such as labels and templates is kept, as is address activity,
+            // which also records addresses used only by since replaced transactions
+            for (const auto subaccount : subaccounts) {
+                m_tx_list_caches.purge(subaccount);
+                m_subaccounts[subaccount].erase("satoshi");
+                for (const auto& key :
+                    { "balancehistory", "spendingsummary", "knownscripts", "addressactivityheight" }) {
+                    m_cache.clear_keyvalue(key + std::to_string(subaccount));
+                }
+            }
+            m_cache.clear_keyvalue(CACHE_UPCOMING_NLOCKTIME);
+            m_cache.clear_keyvalue("walletspends");
+        }
+
+        nlohmann::json results = nlohmann::json::array();
+        for (size_t i = 0; i < subaccounts.size(); ++i) {
+            const uint32_t subaccount = subaccounts[i];
+            size_t num_transactions = 0;
+            for_each_transaction(subaccount, [&num_transactions](const nlohmann::json&) { ++num_transactions; });
+            // Update from the freshly fetched transactions; this also saves the cache
+            get_address_activity(subaccount);
+
+            const nlohmann::json result = { { "subaccount", subaccount }, { "num_transactions", num_transactions } };
+            nlohmann::json progress = result;
+            progress["completed"] = i + 1;
+            progress["total"] = subaccounts.size();
+            emit_notification("rescan", progress);
+            results.push_back(result);
+        }
+        return { { "subaccounts", results } };
+    }
+
+    nlohmann::json ga_session::export_unblinded_outputs(const nlohmann::json& details)
+    {
+        GDK_RUNTIME_ASSERT(m_net_params.liquid());
+        const uint32_t subaccount = details.at("subaccount");
+        const auto recipient_pubkey = h2b(details.at("public_key"));
+        GDK_RUNTIME_ASSERT_MSG(recipient_pubkey.size() == EC_PUBLIC_KEY_LEN, "Invalid public key");
+
+        // Collect the unblinded details of our outputs in the date range
+        constexpr uint32_t page_size = 30;
+        nlohmann::json outputs = nlohmann::json::array();
+        nlohmann::json query = { { "subaccount", subaccount }, { "count", page_size },
+            { "from", json_get_value(details, "from") }, { "to", json_get_value(details, "to") } };
+        for (uint32_t first = 0;; first += page_size) {
+            query["first"] = first;
+            const auto txs = get_transactions(query);
+            for (const auto& tx : txs) {
+                for (const auto& ep : tx.at("outputs")) {
+                    if (!json_get_value(ep, "is_relevant", false) || ep.contains("error") || !ep.contains("abf")) {
+                        continue;
+                    }
+                    outputs.push_back({ { "txhash", tx.at("txhash") }, { "pt_idx", ep.at("pt_idx") },
+                        { "asset_id", ep.at("asset_id") }, { "satoshi", ep.at("satoshi") }, { "abf", ep.at("abf") },
+                        { "vbf", ep.at("vbf") } });
+                }
+            }
+            if (txs.size() < page_size) {
+                break;
+            }
+        }
+
+        // Encrypt to the recipient using a shared secret from an ephemeral key,
+        // authenticating the ciphertext with a separately derived key
+        const auto ephemeral_keypair = get_ephemeral_keypair();
+        const auto shared_secret = ecdh(recipient_pubkey, ephemeral_keypair.first);
+        const auto encryption_key = hmac_sha256(shared_secret, ustring_span("encryption"));
+        const auto authentication_key = hmac_sha256(shared_secret, ustring_span("authentication"));
+
+        const std::string plaintext = nlohmann::json({ { "outputs", outputs } }).dump();
+        const std::string ciphertext = aes_cbc_encrypt(encryption_key, plaintext);
+        return { { "ephemeral_public_key", b2h(ephemeral_keypair.second) },
+            { "ciphertext", ciphertext }, { "hmac", b2h(hmac_sha256(authentication_key, h2b(ciphertext))) } };
+    }
+
+    // Returns the scripts of the wallet's own addresses, and of those the subaccount has paid.
+    // These are persisted by address, along with the highest block whose transactions have
+    // been examined
+    std::pair<std::set<std::string>, std::set<std::string>> ga_session::get_known_scripts(uint32_t subaccount)
+    {
+        const std::string cache_key = "knownscripts" + std::to_string(subaccount);
+        nlohmann::json known;
+        std::vector<std::string> generated;
+        {
+            locker_t locker(m_mutex);
+            const auto value = m_cache.get(cache_key);
+            known = value ? nlohmann::json::from_msgpack(value->begin(), value->end())
+                          : nlohmann::json({ { "own", nlohmann::json::object() },
+                              { "paid", nlohmann::json::object() }, { "block_height", 0 } });
+            for (const auto& sa : m_subaccounts) {
+                for (const auto& address : m_cache.get_generated_addresses(sa.first)) {
+                    generated.emplace_back(address.at("address").get<std::string>());
+                }
+            }
+        }
+
+        bool changed = false;
+        const auto add_script = [this, &changed](nlohmann::json& scripts, const std::string& address) {
+            if (scripts.find(address) != scripts.end()) {
+                return;
+            }
+            try {
+                scripts[address] = b2h(scriptpubkey_from_address(m_net_params, address));
+                changed = true;
+            } catch (const std::exception&) {
+                // Ignore addresses we can't parse
+            }
+        };
+
+        auto& own = known["own"];
+        auto& paid = known["paid"];
+        for (const auto& address : generated) {
+            add_script(own, address);
+        }
+
+        // Addresses generated before they were recorded are found from the tx history
+        const uint32_t examined_height = known.at("block_height");
+        const uint32_t highest_height = for_each_new_transaction(
+            subaccount, examined_height, [&own, &paid, &add_script](const nlohmann::json& tx) {
+                const bool is_outgoing = tx.at("type") == "outgoing";
+                for (const auto& ep : tx.at("outputs")) {
+                    const std::string address = ep.at("address");
+                    if (address.empty()) {
+                        continue;
+                    }
+                    if (json_get_value(ep, "is_relevant", false)) {
+                        add_script(own, address);
+                    } else if (is_outgoing) {
+                        add_script(paid, address);
+                    }
+                }
+            });
+
+        if (changed || highest_height != examined_height) {
+            known["block_height"] = highest_height;
+            locker_t locker(m_mutex);
+            m_cache.upsert_keyvalue(cache_key, nlohmann::json::to_msgpack(known));
+            if (m_local_encryption_key) {
+                m_cache.save_db(m_local_encryption_key.get());
+            }
+        }
+
+        std::set<std::string> own_scripts, paid_scripts;
+        for (const auto& item : own.items()) {
+            own_scripts.emplace(item.value().get<std::string>());
+        }
+        for (const auto& item : paid.items()) {
+            paid_scripts.emplace(item.value().get<std::string>());
+        }
+        return std::make_pair(std::move(own_scripts), std::move(paid_scripts));
     }
 
     std::vector<nlohmann::json> ga_session::get_transactions(uint32_t subaccount, uint32_t page_id)
@@ -2164,6 +3581,8 @@ namespace sdk {
         nlohmann::json txs;
         wamp_call([&txs](wamp_call_result result) { txs = get_json_result(result.get()); },
             "com.greenaddress.txs.get_list_v2", page_id, std::string(), std::string(), std::string(), subaccount);
+        add_sync_progress("pages_fetched", 1);
+        add_sync_progress("transactions_fetched", static_cast<uint32_t>(txs["list"].size()));
 
         {
             locker_t locker(m_mutex);
@@ -2190,18 +3609,28 @@ namespace sdk {
         txs["page_id"] = page_id;
         json_add_if_missing(txs, "next_page_id", 0, true);
 
-        // Remove all replaced transactions
-        // TODO: Add 'replaces' to txs that were bumped, and mark replaced
-        // txs that aren't in our list as double spent
         std::vector<nlohmann::json> tx_list;
         tx_list.reserve(txs["list"].size());
         for (auto& tx_details : txs["list"]) {
-            if (tx_details.find("replaced_by") == tx_details.end()) {
-                tx_list.emplace_back(tx_details);
-            }
+            tx_list.emplace_back(tx_details);
         }
 
         const auto is_liquid = m_net_params.liquid();
+        const auto denied_scripts = get_denied_scripts();
+        std::map<std::string, std::set<std::string>> unconfirmed_spends;
+        nlohmann::json issuances;
+        std::set<std::string> change_addresses;
+        {
+            locker_t locker(m_mutex);
+            if (is_liquid) {
+                issuances = get_issuances(locker);
+            }
+            for (const auto& generated : m_cache.get_generated_addresses(subaccount)) {
+                if (generated.value("source", std::string()) == "create_transaction") {
+                    change_addresses.insert(generated.at("address").get<std::string>());
+                }
+            }
+        }
         for (auto& tx_details : tx_list) {
             const uint32_t tx_block_height = json_add_if_missing(tx_details, "block_height", 0, true);
             // TODO: Server should set subaccount to null if this is a spend from multiple subaccounts
@@ -2226,6 +3655,9 @@ namespace sdk {
                     tx_data, WALLY_TX_FLAG_USE_WITNESS | (m_net_params.liquid() ? WALLY_TX_FLAG_USE_ELEMENTS : 0));
 
                 update_tx_info(m_net_params, tx, tx_details);
+                if (tx_block_height == 0) {
+                    unconfirmed_spends.emplace(tx_details.at("txhash").get<std::string>(), get_spent_outpoints(tx));
+                }
             } else {
                 tx_details["transaction_size"] = tx_size;
                 if (tx_details.find("vsize") == tx_details.end() || tx_details["vsize"].is_null()) {
@@ -2257,6 +3689,30 @@ namespace sdk {
                 json_add_if_missing(ep, "address", std::string(), true);
                 ep.erase("is_credit");
 
+                // The script paid to, or spent from for inputs. Liquid endpoints
+                // carry their script, which is empty for fees
+                const std::string ep_address = ep["address"];
+                std::vector<unsigned char> ep_script;
+                bool have_script = false;
+                try {
+                    if (is_liquid && ep.contains("script")) {
+                        ep_script = h2b(ep["script"]);
+                        have_script = true;
+                    } else if (!ep_address.empty()) {
+                        ep_script = scriptpubkey_from_address(m_net_params, ep_address);
+                        have_script = true;
+                    }
+                } catch (const std::exception&) {
+                    // Unparseable scripts and addresses are unknown and cannot be denied
+                }
+
+                // Flag payments from or to denied addresses
+                if (!ep_script.empty() && denied_scripts.count(b2h(ep_script)) != 0) {
+                    ep["is_denied"] = true;
+                    tx_details["has_denied_address"] = true;
+                }
+                ep["output_type"] = have_script ? get_output_type(ep_script) : "unknown";
+
                 const bool is_tx_output = json_get_value(ep, "is_output", false);
                 const bool is_relevant = json_get_value(ep, "is_relevant", false);
 
@@ -2299,18 +3755,32 @@ namespace sdk {
             // TODO: improve the detection of tx type.
             bool net_positive{ false };
             bool net_positive_set{ false };
+            bool is_mixed{ false };
+            std::vector<std::string> incoming_assets;
             for (const auto& asset_id : unique_asset_ids) {
                 const auto net_received = received[asset_id];
                 const auto net_spent = spent[asset_id];
                 const auto asset_net_positive = net_received > net_spent;
                 if (net_positive_set) {
-                    GDK_RUNTIME_ASSERT_MSG(net_positive == asset_net_positive, "Ambiguous tx direction");
+                    if (net_positive != asset_net_positive) {
+                        // Only Liquid txs such as issuances can both send and receive
+                        GDK_RUNTIME_ASSERT_MSG(is_liquid, "Ambiguous tx direction");
+                        is_mixed = true;
+                    }
                 } else {
                     net_positive = asset_net_positive;
                     net_positive_set = true;
                 }
-                const amount total = net_positive ? net_received - net_spent : net_spent - net_received;
+                const amount total = asset_net_positive ? net_received - net_spent : net_spent - net_received;
                 tx_details["satoshi"][asset_id] = total.value();
+                if (asset_net_positive) {
+                    incoming_assets.emplace_back(asset_id);
+                }
+            }
+            if (is_mixed) {
+                // Describe the tx by what it sends, and list the assets it receives
+                net_positive = false;
+                tx_details["incoming_assets"] = incoming_assets;
             }
 
             const bool is_confirmed = tx_block_height != 0;
@@ -2368,13 +3838,160 @@ namespace sdk {
                 tx_details["can_rbf"] = !is_confirmed && json_get_value(tx_details, "rbf_optin", false);
                 tx_details["can_cpfp"] = false;
             }
+            const auto issuance_p = issuances.find(json_get_value(tx_details, "txhash"));
+            if (issuance_p != issuances.end()) {
+                tx_details["type"] = "issuance";
+                tx_details["issuance"] = *issuance_p;
+                tx_details["can_rbf"] = false;
+            } else if (is_mixed) {
+                tx_details["type"] = "mixed";
+            }
+
+            // Our outputs in a tx spending our coins are change. A re-deposit pays
+            // only ourselves, so only the outputs we generated as change are
+            const bool is_spend = spends_wallet_coins(tx_details);
+            const bool is_redeposit = tx_details["type"] == "redeposit";
+            for (auto& output : tx_details["outputs"]) {
+                bool is_change = is_spend && json_get_value(output, "is_relevant", false);
+                if (is_change && is_redeposit) {
+                    is_change = change_addresses.count(json_get_value(output, "address")) != 0;
+                }
+                output["is_change"] = is_change;
+            }
 
             tx_details["addressees"] = addressees;
             tx_details["user_signed"] = true;
             tx_details["server_signed"] = true;
         }
 
-        return tx_list;
+        // Let callers show progress while history is being fetched and unblinded
+        nlohmann::json progress = get_sync_status();
+        progress["subaccount"] = subaccount;
+        progress["page"] = page_id;
+        emit_notification("sync", progress);
+
+        mark_conflicted_transactions(tx_list, unconfirmed_spends, page_id == 0, txs["next_page_id"] == 0);
+        return group_replaced_transactions(tx_list);
+    }
+
+    // Mark unconfirmed transactions whose inputs have been spent by a confirmed
+    // transaction as conflicted, so they aren't shown as payments still pending.
+    // The outpoints spent by wallet transactions are kept in the cache, so that
+    // conflicts are found across pages and each tx is only examined once
+    void ga_session::mark_conflicted_transactions(std::vector<nlohmann::json>& tx_list,
+        const std::map<std::string, std::set<std::string>>& unconfirmed_spends, bool is_first_page, bool is_last_page)
+    {
+        nlohmann::json spends;
+        {
+            locker_t locker(m_mutex);
+            const auto value = m_cache.get("walletspends");
+            spends = value ? nlohmann::json::from_msgpack(value->begin(), value->end())
+                           : nlohmann::json({ { "unconfirmed", nlohmann::json::object() },
+                               { "confirmed", nlohmann::json::object() }, { "conflicted", nlohmann::json::object() } });
+        }
+        auto& unconfirmed = spends["unconfirmed"]; // txhash -> outpoints spent and created_at
+        auto& confirmed = spends["confirmed"]; // outpoint -> spending txhash
+        auto& conflicted = spends["conflicted"]; // txhash -> conflicting txhash and created_at
+        bool changed = false;
+
+        // Unconfirmed txs are listed with their hex, giving their outpoints for free
+        std::set<std::string> page_txhashes;
+        for (const auto& tx_details : tx_list) {
+            const std::string txhash = tx_details.at("txhash");
+            page_txhashes.insert(txhash);
+            const auto p = unconfirmed_spends.find(txhash);
+            if (p != unconfirmed_spends.end() && spends_wallet_coins(tx_details)
+                && unconfirmed.find(txhash) == unconfirmed.end() && conflicted.find(txhash) == conflicted.end()) {
+                unconfirmed[txhash] = { { "outpoints", p->second }, { "created_at", tx_details.at("created_at") } };
+                changed = true;
+            }
+        }
+
+        // Record the outpoints of confirmed txs spending our coins. Those seen while
+        // unconfirmed are already known. Others are fetched, but only while there are
+        // unconfirmed spends they could conflict with
+        std::set<std::string> indexed;
+        for (const auto& item : confirmed.items()) {
+            indexed.insert(item.value().get<std::string>());
+        }
+        const uint32_t flags = WALLY_TX_FLAG_USE_WITNESS | (m_net_params.liquid() ? WALLY_TX_FLAG_USE_ELEMENTS : 0);
+        for (const auto& tx_details : tx_list) {
+            const std::string txhash = tx_details.at("txhash");
+            if (tx_details.at("block_height") == 0 || !spends_wallet_coins(tx_details) || indexed.count(txhash)) {
+                continue;
+            }
+            std::set<std::string> outpoints;
+            const auto p = unconfirmed.find(txhash);
+            if (p != unconfirmed.end()) {
+                outpoints = p->at("outpoints").get<std::set<std::string>>();
+                unconfirmed.erase(p);
+            } else if (!unconfirmed.empty()) {
+                outpoints = get_spent_outpoints(tx_from_hex(get_transaction_details(txhash).at("transaction"), flags));
+            } else {
+                continue;
+            }
+            for (const auto& outpoint : outpoints) {
+                confirmed[outpoint] = txhash;
+            }
+            indexed.insert(txhash);
+            changed = true;
+        }
+
+        // Conflicted txs can never confirm, so stop tracking what they spend
+        for (auto item = unconfirmed.begin(); item != unconfirmed.end();) {
+            std::string conflicted_by;
+            for (const auto& outpoint : item->at("outpoints")) {
+                const auto p = confirmed.find(outpoint.get<std::string>());
+                if (p != confirmed.end()) {
+                    conflicted_by = p->get<std::string>();
+                    break;
+                }
+            }
+            if (conflicted_by.empty()) {
+                ++item;
+                continue;
+            }
+            conflicted[item.key()] = { { "conflicted_by", conflicted_by }, { "created_at", item->at("created_at") } };
+            item = unconfirmed.erase(item);
+            changed = true;
+        }
+
+        // The list is ordered newest first, so a tx created within the time span
+        // of this page but missing from it is no longer listed by the server
+        const std::string newest = tx_list.empty() ? std::string() : tx_list.front().at("created_at");
+        const std::string oldest = tx_list.empty() ? std::string() : tx_list.back().at("created_at");
+        const auto evict_unlisted = [&](nlohmann::json& entries) {
+            for (auto item = entries.begin(); item != entries.end();) {
+                const std::string created_at = item->at("created_at");
+                if (!page_txhashes.count(item.key()) && (is_first_page || created_at < newest)
+                    && (is_last_page || created_at > oldest)) {
+                    item = entries.erase(item);
+                    changed = true;
+                } else {
+                    ++item;
+                }
+            }
+        };
+        evict_unlisted(unconfirmed);
+        evict_unlisted(conflicted);
+
+        if (changed) {
+            locker_t locker(m_mutex);
+            m_cache.upsert_keyvalue("walletspends", nlohmann::json::to_msgpack(spends));
+            if (m_local_encryption_key) {
+                m_cache.save_db(m_local_encryption_key.get());
+            }
+        }
+
+        for (auto& tx_details : tx_list) {
+            const auto p = conflicted.find(tx_details.at("txhash").get<std::string>());
+            if (p != conflicted.end()) {
+                tx_details["status"] = "conflicted";
+                tx_details["conflicted_by"] = p->at("conflicted_by");
+                tx_details["can_rbf"] = false;
+                tx_details["can_cpfp"] = false;
+            }
+        }
     }
 
     autobahn::wamp_subscription ga_session::subscribe(
@@ -2418,16 +4035,367 @@ namespace sdk {
             unique_unlock unlocker(locker);
             handler(context, details_c);
         }
-        if (details_c == nullptr) {
-            set_notification_handler(locker, nullptr, nullptr);
+        if (details_c == nullptr) {
+            set_notification_handler(locker, nullptr, nullptr);
+        }
+    }
+
+    amount ga_session::get_dust_threshold() const
+    {
+        const uint32_t network_dust = m_net_params.dust_threshold();
+        if (network_dust != 0) {
+            // The network configuration overrides the server provided value
+            return amount(network_dust);
+        }
+        locker_t locker(m_mutex);
+        const amount::value_type v = m_login_data.at("dust");
+        return amount(v);
+    }
+
+    nlohmann::json ga_session::get_network_details() const
+    {
+        nlohmann::json policy = { { "dust_relay_fee_rate", m_net_params.dust_relay_fee_rate() },
+            { "dust_thresholds", m_net_params.dust_thresholds() } };
+
+        locker_t locker(m_mutex);
+        policy["min_fee_rate"] = m_min_fee_rate;
+        uint32_t block = 0;
+        if (m_login_data.is_object()) {
+            // Values returned by the server are only available once logged in
+            const auto appearance = m_login_data.value("appearance", nlohmann::json::object());
+            block = json_get_value(appearance, "required_num_blocks", 0u);
+            GDK_RUNTIME_ASSERT(block < NUM_FEE_ESTIMATES);
+        }
+        policy["default_fee_rate"] = m_fee_estimates[block];
+        const uint32_t network_dust = m_net_params.dust_threshold();
+        if (network_dust != 0) {
+            policy["dust_threshold"] = network_dust;
+        } else {
+            policy["dust_threshold"] = json_get_value(m_login_data, "dust", nlohmann::json());
+        }
+
+        nlohmann::json details = m_net_params.get_json();
+        details["policy"] = std::move(policy);
+        return details;
+    }
+
+    void ga_session::record_event(ga_session::locker_t& locker, const std::string& event_type, nlohmann::json details)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+
+        const auto now = std::chrono::system_clock::now().time_since_epoch();
+        details["event"] = event_type;
+        details["timestamp"] = std::chrono::duration_cast<std::chrono::seconds>(now).count();
+        // Not saved here: callers save once along with any other cache changes
+        m_cache.insert_event(details);
+    }
+
+    nlohmann::json ga_session::get_event_journal()
+    {
+        locker_t locker(m_mutex);
+        return { { "events", m_cache.get_events() } };
+    }
+
+    nlohmann::json ga_session::get_cache_stats()
+    {
+        locker_t locker(m_mutex);
+        return m_cache.get_stats();
+    }
+
+    nlohmann::json ga_session::get_denylist()
+    {
+        locker_t locker(m_mutex);
+        return get_denylist(locker);
+    }
+
+    nlohmann::json ga_session::get_denylist(locker_t& locker)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        const auto value = m_cache.get("denylist");
+        if (!value) {
+            return { { "addresses", nlohmann::json::array() }, { "scripts", nlohmann::json::array() } };
+        }
+        return nlohmann::json::from_msgpack(value->begin(), value->end());
+    }
+
+    void ga_session::set_denylist(const nlohmann::json& denylist)
+    {
+        // Validate the entries before storing them. The scripts they deny are stored
+        // normalized to lower case hex, so they can be matched without re-deriving them
+        const std::vector<std::string> addresses = denylist.value("addresses", std::vector<std::string>{});
+        std::vector<std::string> scripts = denylist.value("scripts", std::vector<std::string>{});
+        std::set<std::string> denied_scripts;
+        for (const auto& address : addresses) {
+            denied_scripts.emplace(b2h(scriptpubkey_from_address(m_net_params, address)));
+        }
+        for (auto& script : scripts) {
+            const auto script_bytes = h2b(script);
+            GDK_RUNTIME_ASSERT_MSG(!script_bytes.empty(), "invalid script");
+            script = b2h(script_bytes);
+            denied_scripts.emplace(script);
+        }
+
+        const nlohmann::json stored = { { "addresses", addresses }, { "scripts", scripts } };
+        locker_t locker(m_mutex);
+        m_cache.upsert_keyvalue("denylist", nlohmann::json::to_msgpack(stored));
+        m_cache.upsert_keyvalue("deniedscripts", nlohmann::json::to_msgpack(nlohmann::json(denied_scripts)));
+        record_event(locker, "denylist_changed", stored);
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
+    }
+
+    nlohmann::json ga_session::get_address_labels(uint32_t subaccount)
+    {
+        locker_t locker(m_mutex);
+        return get_address_labels(locker, subaccount);
+    }
+
+    nlohmann::json ga_session::get_address_labels(locker_t& locker, uint32_t subaccount)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        const auto value = m_cache.get("addresslabels" + std::to_string(subaccount));
+        if (!value) {
+            return nlohmann::json::object();
+        }
+        return nlohmann::json::from_msgpack(value->begin(), value->end());
+    }
+
+    nlohmann::json ga_session::get_address_labels(const nlohmann::json& details)
+    {
+        const uint32_t subaccount = details.at("subaccount");
+        nlohmann::json labels = nlohmann::json::array();
+        for (const auto& item : get_address_labels(subaccount).items()) {
+            labels.push_back({ { "pointer", std::stoul(item.key()) }, { "label", item.value() } });
+        }
+        return { { "labels", labels } };
+    }
+
+    void ga_session::set_address_label(const nlohmann::json& details)
+    {
+        const uint32_t subaccount = details.at("subaccount");
+        const uint32_t pointer = details.at("pointer");
+        const std::string label = details.at("label");
+
+        // Hold the lock while updating, so concurrent changes aren't lost
+        locker_t locker(m_mutex);
+        auto labels = get_address_labels(locker, subaccount);
+        if (label.empty()) {
+            labels.erase(std::to_string(pointer));
+        } else {
+            labels[std::to_string(pointer)] = label;
+        }
+        m_cache.upsert_keyvalue("addresslabels" + std::to_string(subaccount), nlohmann::json::to_msgpack(labels));
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
+    }
+
+    nlohmann::json ga_session::get_transaction_templates(locker_t& locker)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        const auto value = m_cache.get("transactiontemplates");
+        if (!value) {
+            return nlohmann::json::object();
+        }
+        return nlohmann::json::from_msgpack(value->begin(), value->end());
+    }
+
+    nlohmann::json ga_session::get_transaction_templates()
+    {
+        locker_t locker(m_mutex);
+        nlohmann::json templates = nlohmann::json::array();
+        for (const auto& item : get_transaction_templates(locker).items()) {
+            templates.push_back({ { "name", item.key() }, { "template", item.value() } });
+        }
+        return { { "templates", templates } };
+    }
+
+    void ga_session::set_transaction_template(const nlohmann::json& details)
+    {
+        const std::string name = details.at("name");
+        GDK_RUNTIME_ASSERT_MSG(!name.empty(), "Invalid template name");
+
+        nlohmann::json tx_template;
+        const auto p = details.find("template");
+        if (p != details.end() && !p->is_null()) {
+            const auto& addressees = p->at("addressees");
+            GDK_RUNTIME_ASSERT_MSG(addressees.is_array() && !addressees.empty(), "Template has no addressees");
+            // Only keep the elements that describe the payment, not any tx state
+            for (const auto& key : { "subaccount", "addressees", "memo", "fee_rate", "fee_preset" }) {
+                if (p->contains(key)) {
+                    tx_template[key] = p->at(key);
+                }
+            }
+        }
+
+        locker_t locker(m_mutex);
+        auto templates = get_transaction_templates(locker);
+        if (tx_template.is_null()) {
+            templates.erase(name);
+        } else {
+            templates[name] = tx_template;
+        }
+        m_cache.upsert_keyvalue("transactiontemplates", nlohmann::json::to_msgpack(templates));
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
+    }
+
+    // Fill in any elements the caller hasn't given from the named template
+    nlohmann::json ga_session::apply_transaction_template(const nlohmann::json& details)
+    {
+        const std::string name = details.at("template");
+        nlohmann::json tx_template;
+        {
+            locker_t locker(m_mutex);
+            const auto templates = get_transaction_templates(locker);
+            const auto p = templates.find(name);
+            GDK_RUNTIME_ASSERT_MSG(p != templates.end(), "Unknown transaction template");
+            tx_template = *p;
+        }
+
+        nlohmann::json result(details);
+        for (const auto& item : tx_template.items()) {
+            if (item.key() == "memo" && !result.contains("memo")) {
+                // Memos may contain {date}, replaced with the current date
+                std::string memo = item.value();
+                const auto now = std::chrono::system_clock::to_time_t(std::chrono::system_clock::now());
+                std::array<char, 11> date;
+                const auto written = std::strftime(date.data(), date.size(), "%Y-%m-%d", std::gmtime(&now));
+                GDK_RUNTIME_ASSERT(written != 0);
+                boost::algorithm::replace_all(memo, "{date}", date.data());
+                result["memo"] = memo;
+            } else {
+                json_add_if_missing(result, item.key(), item.value());
+            }
+        }
+        return result;
+    }
+
+    // Pay a fresh receive address of the subaccount being transferred to. This is
+    // only done once; later calls with the returned details reuse the address
+    nlohmann::json ga_session::add_transfer_addressee(const nlohmann::json& details)
+    {
+        GDK_RUNTIME_ASSERT_MSG(!m_net_params.liquid(), "Internal transfers are not supported on Liquid");
+        const uint32_t subaccount = json_get_value(details, "subaccount", 0u);
+        const uint32_t to_subaccount = details.at("transfer_to_subaccount");
+        GDK_RUNTIME_ASSERT_MSG(to_subaccount != subaccount, "Cannot transfer to the same subaccount");
+        {
+            locker_t locker(m_mutex);
+            GDK_RUNTIME_ASSERT_MSG(m_subaccounts.find(to_subaccount) != m_subaccounts.end(), "Unknown subaccount");
+        }
+
+        const auto address = get_receive_address(to_subaccount, std::string(), "internal_transfer");
+        nlohmann::json addressee = { { "address", address.at("address") } };
+        if (details.contains("satoshi")) {
+            addressee["satoshi"] = details["satoshi"];
+        }
+        nlohmann::json result(details);
+        result["addressees"] = { addressee };
+        return result;
+    }
+
+    // Add details that are held locally rather than by the server to the given
+    // transactions: internal transfer flags, the user's address labels, and fiat
+    // values at the current rate
+    void ga_session::add_local_details(uint32_t subaccount, std::vector<nlohmann::json>& txs)
+    {
+        {
+            locker_t locker(m_mutex);
+            if (m_net_params.liquid()) {
+                for (auto& tx : txs) {
+                    tx["assets"] = get_assets_metadata(locker, tx.at("satoshi"));
+                }
+            }
+            if (!m_fiat_rate.empty()) {
+                for (auto& tx : txs) {
+                    const auto& satoshi = tx.at("satoshi");
+                    if (satoshi.contains("btc")) {
+                        const auto converted = convert_amount(locker, { { "satoshi", satoshi["btc"] } });
+                        tx["fiat"] = { { "fiat", converted.at("fiat") }, { "fiat_currency", m_fiat_currency },
+                            { "fiat_rate", m_fiat_rate }, { "fiat_rate_updated", m_fiat_rate_updated } };
+                    }
+                }
+            }
+        }
+
+        for (auto& tx : txs) {
+            // Mark transactions moving funds between the wallet's own subaccounts
+            std::set<uint32_t> from;
+            std::set<uint32_t> to;
+            for (const auto& ep : tx["inputs"]) {
+                if (json_get_value(ep, "is_relevant", false)) {
+                    from.insert(ep.at("subaccount").get<uint32_t>());
+                }
+            }
+            for (const auto& ep : tx["outputs"]) {
+                if (json_get_value(ep, "is_relevant", false) && !from.count(ep.at("subaccount").get<uint32_t>())) {
+                    to.insert(ep.at("subaccount").get<uint32_t>());
+                }
+            }
+            tx["is_internal_transfer"] = !from.empty() && !to.empty();
+            if (!from.empty() && !to.empty()) {
+                tx["internal_transfer"] = { { "from_subaccounts", from }, { "to_subaccounts", to } };
+            }
+        }
+
+        const auto labels = get_address_labels(subaccount);
+        if (labels.empty()) {
+            return;
+        }
+        for (auto& tx : txs) {
+            for (const auto& key : { "inputs", "outputs" }) {
+                for (auto& ep : tx[key]) {
+                    if (!json_get_value(ep, "is_relevant", false) || ep.at("subaccount") != subaccount) {
+                        continue;
+                    }
+                    const auto p = labels.find(std::to_string(ep.at("pointer").get<uint32_t>()));
+                    if (p != labels.end()) {
+                        ep["address_label"] = *p;
+                    }
+                }
+            }
+        }
+    }
+
+    std::set<std::string> ga_session::get_denied_scripts()
+    {
+        nlohmann::json denylist;
+        {
+            locker_t locker(m_mutex);
+            const auto value = m_cache.get("deniedscripts");
+            if (value) {
+                return nlohmann::json::from_msgpack(value->begin(), value->end()).get<std::set<std::string>>();
+            }
+            denylist = get_denylist(locker);
+        }
+        // Denylists stored without their scripts are derived here
+        std::set<std::string> scripts;
+        for (const auto& address : denylist["addresses"]) {
+            scripts.emplace(b2h(scriptpubkey_from_address(m_net_params, address)));
+        }
+        for (const auto& script : denylist["scripts"]) {
+            scripts.emplace(b2h(h2b(script)));
         }
+        return scripts;
     }
 
-    amount ga_session::get_dust_threshold() const
+    bool ga_session::is_denied_address(const std::string& address)
     {
-        locker_t locker(m_mutex);
-        const amount::value_type v = m_login_data.at("dust");
-        return amount(v);
+        std::vector<unsigned char> script;
+        try {
+            script = scriptpubkey_from_address(m_net_params, address);
+        } catch (const std::exception&) {
+            return false; // Invalid addresses are reported by the caller
+        }
+        return is_denied_script(script);
+    }
+
+    bool ga_session::is_denied_script(byte_span_t script)
+    {
+        const auto scripts = get_denied_scripts();
+        return scripts.count(b2h(script)) != 0;
     }
 
     nlohmann::json ga_session::get_blinded_scripts(const nlohmann::json& details)
@@ -2436,9 +4404,11 @@ namespace sdk {
 
         nlohmann::json answer = nlohmann::json::array();
         std::unordered_set<std::pair<std::string, std::string>, BlindingNoncesHash> no_dups;
+        const uint32_t cancel_id = m_cancel_id;
 
         // there's an hard-limit of 30 pages from the backend, see https://api.greenaddress.it/txs.html#get_list_v2
         for (size_t page_id = 0; page_id < 30; ++page_id) {
+            check_cancelled(cancel_id);
             nlohmann::json txs;
 
             if (details.contains("subaccount") && details.at("subaccount").is_number()) {
@@ -2525,6 +4495,110 @@ namespace sdk {
         m_cache.insert_liquidblindingnonce(h2b(pubkey), h2b(script), h2b(nonce));
     }
 
+    nlohmann::json ga_session::get_frozen_utxos(locker_t& locker)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        const auto value = m_cache.get("frozenutxos");
+        if (!value) {
+            return nlohmann::json::object();
+        }
+        return nlohmann::json::from_msgpack(value->begin(), value->end());
+    }
+
+    void ga_session::set_utxo_frozen(const nlohmann::json& details, bool is_frozen)
+    {
+        const std::string txhash = details.at("txhash");
+        const uint32_t pt_idx = details.at("pt_idx");
+        GDK_RUNTIME_ASSERT_MSG(h2b(txhash).size() == SHA256_LEN, "invalid txhash");
+        const std::string key = txhash + ":" + std::to_string(pt_idx);
+
+        locker_t locker(m_mutex);
+        auto frozen = get_frozen_utxos(locker);
+        if (is_frozen) {
+            frozen[key] = true;
+        } else {
+            frozen.erase(key);
+        }
+        m_cache.upsert_keyvalue("frozenutxos", nlohmann::json::to_msgpack(frozen));
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
+    }
+
+    nlohmann::json ga_session::get_utxo_policy(locker_t& locker)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        nlohmann::json policy = { { "dust_satoshi", 0 }, { "unknown_assets", false } };
+        const auto value = m_cache.get("utxopolicy");
+        if (value) {
+            policy.update(nlohmann::json::from_msgpack(value->begin(), value->end()));
+        }
+        return policy;
+    }
+
+    nlohmann::json ga_session::get_utxo_policy()
+    {
+        locker_t locker(m_mutex);
+        return get_utxo_policy(locker);
+    }
+
+    void ga_session::set_utxo_policy(const nlohmann::json& details)
+    {
+        const uint64_t dust_satoshi = json_get_value(details, "dust_satoshi", uint64_t{ 0 });
+        const bool unknown_assets = json_get_value(details, "unknown_assets", false);
+        GDK_RUNTIME_ASSERT_MSG(!unknown_assets || m_net_params.liquid(), "Assets require Liquid");
+        const nlohmann::json policy = { { "dust_satoshi", dust_satoshi }, { "unknown_assets", unknown_assets } };
+
+        locker_t locker(m_mutex);
+        m_cache.upsert_keyvalue("utxopolicy", nlohmann::json::to_msgpack(policy));
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
+    }
+
+    // Flag the utxos that the policy says should not be spent by default:
+    // small policy asset amounts, as sent in dust attacks, and unknown assets
+    void ga_session::flag_suspicious_utxos(locker_t& locker, nlohmann::json& utxos)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+
+        const auto policy = get_utxo_policy(locker);
+        const uint64_t dust_satoshi = policy.at("dust_satoshi");
+        const bool check_assets = policy.at("unknown_assets");
+
+        std::set<std::string> known_assets;
+        if (check_assets) {
+            std::set<std::string> asset_ids;
+            for (const auto& utxo : utxos) {
+                const std::string asset_id = json_get_value(utxo, "asset_id");
+                if (!asset_id.empty()) {
+                    asset_ids.insert(asset_id);
+                }
+            }
+            for (const auto& asset : get_cached_assets(locker, asset_ids).items()) {
+                known_assets.insert(asset.key());
+            }
+        }
+
+        for (auto& utxo : utxos) {
+            if (utxo.contains("error") || !utxo.contains("satoshi")) {
+                continue;
+            }
+            const auto asset_tag = asset_id_from_string(utxo.value("asset_id", std::string{}));
+            if (asset_tag == "btc") {
+                if (utxo.at("satoshi").get<uint64_t>() < dust_satoshi) {
+                    utxo["suspicious_reason"] = "dust";
+                }
+            } else if (check_assets && !known_assets.count(asset_tag)) {
+                utxo["suspicious_reason"] = "unknown_asset";
+            }
+        }
+    }
+
+    void ga_session::freeze_utxo(const nlohmann::json& details) { set_utxo_frozen(details, true); }
+
+    void ga_session::unfreeze_utxo(const nlohmann::json& details) { set_utxo_frozen(details, false); }
+
     // Idempotent
     nlohmann::json ga_session::get_unspent_outputs(const nlohmann::json& details)
     {
@@ -2557,12 +4631,38 @@ namespace sdk {
 
         cleanup_utxos(utxos, m_net_params.policy_asset());
 
+        {
+            locker_t locker(m_mutex);
+            const auto frozen = get_frozen_utxos(locker);
+            for (auto& utxo : utxos) {
+                if (utxo.contains("txhash")) {
+                    const std::string key = utxo.at("txhash").get<std::string>() + ":"
+                        + std::to_string(utxo.at("pt_idx").get<uint32_t>());
+                    utxo["is_frozen"] = frozen.contains(key);
+                }
+            }
+            flag_suspicious_utxos(locker, utxos);
+        }
+
+        const auto labels = get_address_labels(subaccount);
+        if (!labels.empty()) {
+            for (auto& utxo : utxos) {
+                const auto p = labels.find(std::to_string(json_get_value(utxo, "pointer", 0u)));
+                if (p != labels.end()) {
+                    utxo["address_label"] = *p;
+                }
+            }
+        }
+
         nlohmann::json asset_utxos({});
         std::for_each(
             std::begin(utxos), std::end(utxos), [&asset_utxos, &confidential_only, this](const nlohmann::json& utxo) {
                 const auto has_error = utxo.find("error") != utxo.end();
                 if (has_error) {
                     asset_utxos["error"].emplace_back(utxo);
+                } else if (utxo.contains("suspicious_reason")) {
+                    // Kept apart so they are not spent along with other coins by default
+                    asset_utxos["suspicious"].emplace_back(utxo);
                 } else {
                     const bool confidential_utxo = m_net_params.liquid() && utxo.at("confidential");
                     // either return all or only confidential UTXOs
@@ -2636,20 +4736,182 @@ namespace sdk {
     }
 
     // Idempotent
-    nlohmann::json ga_session::get_transaction_details(const std::string& txhash) const
+    nlohmann::json ga_session::get_transaction_details(const std::string& txhash)
     {
+        // Transactions can't change once their txid is known, so are cached indefinitely
+        const std::string key = "rawtx" + txhash;
         std::string tx_data;
-        wamp_call([&tx_data](wamp_call_result result) { tx_data = result.get().argument<std::string>(0); },
-            "com.greenaddress.txs.get_raw_output", txhash);
+        {
+            locker_t locker(m_mutex);
+            const auto value = m_cache.get(key);
+            if (value) {
+                tx_data = b2h(*value);
+            }
+        }
+        const bool is_cached = !tx_data.empty();
+        if (!is_cached) {
+            wamp_call([&tx_data](wamp_call_result result) { tx_data = result.get().argument<std::string>(0); },
+                "com.greenaddress.txs.get_raw_output", txhash);
+        }
 
         const uint32_t flags = WALLY_TX_FLAG_USE_WITNESS | (m_net_params.liquid() ? WALLY_TX_FLAG_USE_ELEMENTS : 0);
         const auto tx = tx_from_hex(tx_data, flags);
+        GDK_RUNTIME_ASSERT_MSG(get_txhash(tx) == txhash, "Transaction does not match txhash");
         nlohmann::json result = { { "txhash", txhash } };
         update_tx_info(m_net_params, tx, result);
+
+        locker_t locker(m_mutex);
+        add_transaction_io(locker, tx, result);
+        if (!is_cached) {
+            m_cache.upsert_keyvalue(key, h2b(tx_data));
+            if (m_local_encryption_key) {
+                m_cache.save_db(m_local_encryption_key.get());
+            }
+        }
+        return result;
+    }
+
+    // Add the inputs and outputs of a transaction, with any output values we know
+    void ga_session::add_transaction_io(locker_t& locker, const wally_tx_ptr& tx, nlohmann::json& result)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+
+        nlohmann::json inputs = nlohmann::json::array();
+        for (size_t i = 0; i < tx->num_inputs; ++i) {
+            const auto& input = tx->inputs[i];
+            inputs.push_back({ { "pt_idx", i }, { "prevout_txhash", b2h_rev(input.txhash) },
+                { "prevout_pt_idx", input.index }, { "sequence", input.sequence } });
+        }
+        result["transaction_inputs"] = inputs;
+
+        const bool is_liquid = m_net_params.liquid();
+        const auto txhash_bytes = h2b(result.at("txhash").get<std::string>());
+        nlohmann::json outputs = nlohmann::json::array();
+        for (uint32_t i = 0; i < tx->num_outputs; ++i) {
+            const auto& o = tx->outputs[i];
+            const auto script = gsl::make_span(o.script, o.script_len);
+            nlohmann::json output = { { "pt_idx", i }, { "script", b2h(script) }, { "is_fee", o.script_len == 0u },
+                { "output_type", get_output_type(script) } };
+            if (!is_liquid) {
+                output["satoshi"] = o.satoshi;
+            } else if (o.value && o.value_len && *o.value == 1 && o.asset && o.asset_len && *o.asset == 1) {
+                // Explicit (unblinded) value and asset
+                output["satoshi"] = tx_confidential_value_to_satoshi(gsl::make_span(o.value, o.value_len));
+                output["asset_id"] = b2h_rev(gsl::make_span(o.asset + 1, o.asset_len - 1));
+            } else {
+                output["asset_commitment"] = b2h(gsl::make_span(o.asset, o.asset_len));
+                output["value_commitment"] = b2h(gsl::make_span(o.value, o.value_len));
+                output["nonce_commitment"] = b2h(gsl::make_span(o.nonce, o.nonce_len));
+                // Blinded: known only for our own outputs that have been unblinded
+                const auto unblinded = m_cache.get_liquidoutput(txhash_bytes, i);
+                if (unblinded) {
+                    output["satoshi"] = unblinded->at("satoshi");
+                    output["asset_id"] = unblinded->at("asset_id");
+                }
+            }
+            outputs.emplace_back(std::move(output));
+        }
+        result["transaction_outputs"] = outputs;
+    }
+
+    nlohmann::json ga_session::decode_transaction(const nlohmann::json& details)
+    {
+        const uint32_t flags = WALLY_TX_FLAG_USE_WITNESS | (m_net_params.liquid() ? WALLY_TX_FLAG_USE_ELEMENTS : 0);
+        const auto tx = tx_from_hex(details.at("transaction"), flags);
+        nlohmann::json result;
+        update_tx_info(m_net_params, tx, result);
+        {
+            locker_t locker(m_mutex);
+            add_transaction_io(locker, tx, result);
+        }
+
+        // Identify the wallet's inputs and outputs, from the given subaccount
+        const auto subaccount_p = details.find("subaccount");
+        if (subaccount_p == details.end()) {
+            return result;
+        }
+        const uint32_t subaccount = *subaccount_p;
+
+        std::map<std::pair<std::string, uint32_t>, nlohmann::json> utxos;
+        const auto asset_utxos = get_unspent_outputs({ { "subaccount", subaccount }, { "num_confs", 0 } });
+        for (const auto& item : asset_utxos.items()) {
+            if (item.key() == "error") {
+                continue;
+            }
+            for (const auto& utxo : item.value()) {
+                utxos.emplace(std::make_pair<std::string, uint32_t>(utxo.at("txhash"), utxo.at("pt_idx")), utxo);
+            }
+        }
+        for (auto& input : result["transaction_inputs"]) {
+            const auto p = utxos.find(
+                std::make_pair<std::string, uint32_t>(input.at("prevout_txhash"), input.at("prevout_pt_idx")));
+            input["is_relevant"] = p != utxos.end();
+            if (p != utxos.end()) {
+                input["satoshi"] = p->second.at("satoshi");
+                input["subaccount"] = subaccount;
+                if (m_net_params.liquid()) {
+                    input["asset_id"] = p->second.at("asset_id");
+                }
+            }
+        }
+
+        const auto own_scripts = get_known_scripts(subaccount).first;
+        for (auto& output : result["transaction_outputs"]) {
+            output["is_relevant"] = own_scripts.count(output.at("script").get<std::string>()) != 0;
+        }
         return result;
     }
 
-    nlohmann::json ga_session::get_receive_address(uint32_t subaccount, const std::string& addr_type_)
+    nlohmann::json ga_session::get_transaction_blinders(const std::string& txhash)
+    {
+        GDK_RUNTIME_ASSERT(m_net_params.liquid());
+
+        const auto details = get_transaction_details(txhash);
+        const auto tx = tx_from_hex(details.at("transaction"), WALLY_TX_FLAG_USE_WITNESS | WALLY_TX_FLAG_USE_ELEMENTS);
+        const auto txhash_bytes = h2b(txhash);
+
+        locker_t locker(m_mutex);
+        // Blinders of outputs we created are kept when sending, see send_transaction
+        std::map<uint32_t, nlohmann::json> blinders;
+        const auto value = m_cache.get("txblinders" + txhash);
+        if (value) {
+            for (const auto& output : nlohmann::json::from_msgpack(value->begin(), value->end())) {
+                blinders[output.at("index")] = output;
+            }
+        }
+        // Our own outputs are stored once unblinded
+        for (uint32_t i = 0; i < tx->num_outputs; ++i) {
+            if (blinders.count(i) != 0) {
+                continue;
+            }
+            const auto output = m_cache.get_liquidoutput(txhash_bytes, i);
+            if (output) {
+                blinders[i] = { { "index", i }, { "asset_id", output->at("asset_id") },
+                    { "satoshi", output->at("satoshi") }, { "abf", output->at("abf") }, { "vbf", output->at("vbf") } };
+            }
+        }
+
+        // The explorer takes value,asset,vbf,abf for each output, with blinders
+        // in the byte order used by Elements
+        nlohmann::json outputs = nlohmann::json::array();
+        std::vector<std::string> unblinded;
+        for (auto& blinder : blinders) {
+            const auto& output = blinder.second;
+            const auto satoshi = output.at("satoshi").get<amount::value_type>();
+            const std::string vbf = b2h_rev(h2b(output.at("vbf").get<std::string>()));
+            const std::string abf = b2h_rev(h2b(output.at("abf").get<std::string>()));
+            unblinded.emplace_back(
+                std::to_string(satoshi) + "," + output.at("asset_id").get<std::string>() + "," + vbf + "," + abf);
+            outputs.emplace_back(std::move(blinder.second));
+        }
+        const std::string fragment = "#blinded=" + boost::algorithm::join(unblinded, ",");
+        const std::string explorer_url = m_net_params.block_explorer_tx();
+        return { { "txhash", txhash }, { "outputs", outputs }, { "blinded_fragment", fragment },
+            { "unblinded_url", explorer_url.empty() ? std::string() : explorer_url + txhash + fragment } };
+    }
+
+    nlohmann::json ga_session::get_receive_address(
+        uint32_t subaccount, const std::string& addr_type_, const std::string& source)
     {
         std::string addr_type = addr_type_.empty() ? get_default_address_type(subaccount) : addr_type_;
         const bool is_known
@@ -2662,6 +4924,157 @@ namespace sdk {
             "com.greenaddress.vault.fund", subaccount, true, addr_type);
         json_rename_key(address, "addr_type", "address_type");
         GDK_RUNTIME_ASSERT(address["address_type"] == addr_type);
+        add_address_details(address);
+
+        // Record the address so it can later be reconciled with its use
+        const auto now = std::chrono::system_clock::now().time_since_epoch();
+        const nlohmann::json generated = { { "address", address["address"] }, { "subaccount", subaccount },
+            { "pointer", address.at("pointer") }, { "address_type", addr_type },
+            { "created_at", std::chrono::duration_cast<std::chrono::seconds>(now).count() }, { "source", source },
+            { "script", address.at("script") }, { "subtype", address.value("subtype", nlohmann::json()) },
+            { "branch", address.at("branch") } };
+        locker_t locker(m_mutex);
+        m_cache.insert_generated_address(address["address"], subaccount, generated);
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
+        return address;
+    }
+
+    nlohmann::json ga_session::get_change_reservations(locker_t& locker)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        const auto value = m_cache.get("changeaddresses");
+        if (!value) {
+            return nlohmann::json::object();
+        }
+        return nlohmann::json::from_msgpack(value->begin(), value->end());
+    }
+
+    void ga_session::set_change_reservations(locker_t& locker, const nlohmann::json& reservations)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+        m_cache.upsert_keyvalue("changeaddresses", nlohmann::json::to_msgpack(reservations));
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
+    }
+
+    // Change addresses are reserved until a transaction paying them is sent. If
+    // the transaction is abandoned the reservation expires and the address is
+    // reused for a later transaction, rather than leaving a gap of unused addresses
+    nlohmann::json ga_session::get_change_address(uint32_t subaccount)
+    {
+        constexpr uint64_t reservation_secs = 60 * 60;
+        const auto since_epoch = std::chrono::system_clock::now().time_since_epoch();
+        const uint64_t now = std::chrono::duration_cast<std::chrono::seconds>(since_epoch).count();
+        const std::string key = std::to_string(subaccount);
+        const auto is_expired
+            = [now](const nlohmann::json& reservation) { return reservation.at("expires_at").get<uint64_t>() <= now; };
+
+        bool have_expired;
+        {
+            locker_t locker(m_mutex);
+            const auto reservations = get_change_reservations(locker);
+            const auto p = reservations.find(key);
+            have_expired = p != reservations.end() && std::any_of(p->begin(), p->end(), is_expired);
+        }
+
+        if (have_expired) {
+            // A transaction paying an expired reservation may still have been broadcast,
+            // e.g. late or by another client, so never reuse an address with any history
+            const auto activity = get_address_activity(subaccount);
+            const auto is_used = [&activity](const nlohmann::json& reservation) {
+                return activity.count(reservation.at("address").at("pointer")) != 0;
+            };
+
+            locker_t locker(m_mutex);
+            auto reservations = get_change_reservations(locker);
+            auto& subaccount_reservations = reservations[key];
+            subaccount_reservations.erase(std::remove_if(subaccount_reservations.begin(),
+                                              subaccount_reservations.end(), is_used),
+                subaccount_reservations.end());
+            nlohmann::json address;
+            for (auto& reservation : subaccount_reservations) {
+                if (is_expired(reservation)) {
+                    reservation["expires_at"] = now + reservation_secs;
+                    address = reservation.at("address");
+                    break;
+                }
+            }
+            set_change_reservations(locker, reservations);
+            if (!address.is_null()) {
+                return address;
+            }
+        }
+
+        auto address = get_receive_address(subaccount, {}, "create_transaction");
+        locker_t locker(m_mutex);
+        auto reservations = get_change_reservations(locker);
+        reservations[key].push_back({ { "address", address }, { "expires_at", now + reservation_secs } });
+        set_change_reservations(locker, reservations);
+        return address;
+    }
+
+    // Release the reservations of any change addresses paid by a sent transaction
+    void ga_session::release_change_addresses(locker_t& locker, const wally_tx_ptr& tx)
+    {
+        GDK_RUNTIME_ASSERT(locker.owns_lock());
+
+        std::set<std::string> scripts;
+        for (size_t i = 0; i < tx->num_outputs; ++i) {
+            const auto& o = tx->outputs[i];
+            if (o.script_len != 0u) {
+                scripts.insert(b2h(gsl::make_span(o.script, o.script_len)));
+            }
+        }
+
+        auto reservations = get_change_reservations(locker);
+        bool changed = false;
+        for (auto& subaccount_reservations : reservations) {
+            const auto size = subaccount_reservations.size();
+            subaccount_reservations.erase(
+                std::remove_if(subaccount_reservations.begin(), subaccount_reservations.end(),
+                    [&scripts](const auto& r) { return scripts.count(r.at("address").at("scriptpubkey")) != 0; }),
+                subaccount_reservations.end());
+            changed |= subaccount_reservations.size() != size;
+        }
+        if (changed) {
+            set_change_reservations(locker, reservations);
+        }
+    }
+
+    nlohmann::json ga_session::get_previous_address(uint32_t subaccount, uint32_t pointer)
+    {
+        nlohmann::json generated;
+        {
+            locker_t locker(m_mutex);
+            for (const auto& record : m_cache.get_generated_addresses(subaccount)) {
+                if (record.at("pointer") == pointer) {
+                    generated = record;
+                    break;
+                }
+            }
+        }
+        // Only addresses recorded with their script can be recreated
+        GDK_RUNTIME_ASSERT_MSG(!generated.is_null() && generated.contains("script"), "Unknown address");
+
+        // Addresses are generated on the subaccount's receive branch, which
+        // wasn't recorded for addresses generated before it was stored
+        constexpr uint32_t receive_branch = 1;
+        nlohmann::json address = { { "address_type", generated.at("address_type") },
+            { "branch", generated.value("branch", receive_branch) }, { "pointer", pointer },
+            { "script", generated.at("script") }, { "subaccount", subaccount },
+            { "subtype", generated.at("subtype") } };
+        add_address_details(address);
+        GDK_RUNTIME_ASSERT(address.at("address") == generated.at("address"));
+        return address;
+    }
+
+    // Add the script type, address and scriptpubkey to an address returned by the server
+    void ga_session::add_address_details(nlohmann::json& address)
+    {
+        const std::string addr_type = address.at("address_type");
 
         // Add the script type, to allow addresses to be used interchangably with utxos
         script_type addr_script_type;
@@ -2699,16 +5112,35 @@ namespace sdk {
 
         // Only scriptpubkey, we will add the blinding key later
         address["address"] = server_address;
-
-        return address;
+        address["scriptpubkey"] = b2h(scriptpubkey_from_address(m_net_params, server_address));
     }
 
     nlohmann::json ga_session::get_receive_address(const nlohmann::json& details)
     {
         const uint32_t subaccount = details.value("subaccount", 0);
         const std::string addr_type_ = details.value("address_type", std::string{});
+        const auto pointer_p = details.find("pointer");
+        if (pointer_p != details.end()) {
+            // Re-show a previously generated address
+            return get_previous_address(subaccount, *pointer_p);
+        }
+        if (!json_get_value(details, "skip_used", false)) {
+            return get_receive_address(subaccount, addr_type_, "get_receive_address");
+        }
 
-        return get_receive_address(subaccount, addr_type_);
+        // Skip past addresses that have already received funds, e.g. when they
+        // were given out by another device. The number skipped is limited to
+        // avoid moving too far past the last used address when restoring
+        constexpr size_t max_skipped = 20;
+        const auto activity = get_address_activity(subaccount);
+        for (size_t i = 0; i < max_skipped; ++i) {
+            auto address = get_receive_address(subaccount, addr_type_, "get_receive_address");
+            if (activity.count(address.at("pointer")) == 0) {
+                return address;
+            }
+        }
+        GDK_RUNTIME_ASSERT_MSG(false, "Too many used addresses to skip");
+        __builtin_unreachable();
     }
 
     std::string ga_session::blind_address(const std::string& unblinded_addr, const std::string& blinding_key_hex)
@@ -2742,6 +5174,30 @@ namespace sdk {
         return get_subaccount_balance_from_server(subaccount, num_confs);
     }
 
+    nlohmann::json ga_session::get_balance_detailed(const nlohmann::json& details)
+    {
+        const auto balance = get_balance(details);
+
+        nlohmann::json metadata;
+        if (m_net_params.liquid()) {
+            locker_t locker(m_mutex);
+            metadata = get_assets_metadata(locker, balance);
+        }
+
+        nlohmann::json balances = nlohmann::json::array();
+        for (const auto& item : balance.items()) {
+            nlohmann::json entry;
+            if (m_net_params.liquid()) {
+                entry = metadata.at(item.key());
+            } else {
+                entry = { { "asset_id", "btc" }, { "name", "btc" }, { "ticker", "BTC" }, { "precision", 8 } };
+            }
+            entry["satoshi"] = item.value();
+            balances.emplace_back(std::move(entry));
+        }
+        return { { "subaccount", details.at("subaccount") }, { "balances", balances } };
+    }
+
     // Idempotent
     nlohmann::json ga_session::get_available_currencies() const
     {
@@ -3152,6 +5608,12 @@ namespace sdk {
     nlohmann::json ga_session::create_transaction(const nlohmann::json& details)
     {
         try {
+            if (!json_get_value(details, "template").empty()) {
+                return create_ga_transaction(*this, apply_transaction_template(details));
+            }
+            if (details.contains("transfer_to_subaccount") && !details.contains("addressees")) {
+                return create_ga_transaction(*this, add_transfer_addressee(details));
+            }
             return create_ga_transaction(*this, details);
         } catch (const user_error& e) {
             return nlohmann::json({ { "error", e.what() } });
@@ -3160,7 +5622,78 @@ namespace sdk {
 
     nlohmann::json ga_session::sign_transaction(const nlohmann::json& details)
     {
-        return sign_ga_transaction(*this, details);
+        nlohmann::json result = sign_ga_transaction(*this, details);
+
+        nlohmann::json addressees = nlohmann::json::array();
+        for (const auto& addressee : result.value("addressees", nlohmann::json::array())) {
+            nlohmann::json entry = { { "satoshi", addressee.at("satoshi") } };
+            for (const auto& key : { "address", "scriptpubkey", "asset_tag" }) {
+                if (addressee.contains(key)) {
+                    entry[key] = addressee[key];
+                }
+            }
+            addressees.push_back(std::move(entry));
+        }
+        locker_t locker(m_mutex);
+        record_event(locker, "transaction_signed",
+            { { "subaccount", json_get_value(result, "subaccount", 0u) }, { "addressees", std::move(addressees) },
+                { "fee", json_get_value(result, "fee", 0u) } });
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
+        return result;
+    }
+
+    // Check each input we would sign has what signing it requires, without signing
+    nlohmann::json ga_session::can_sign_transaction(const nlohmann::json& details)
+    {
+        const bool is_liquid = m_net_params.liquid();
+        std::vector<nlohmann::json> utxos;
+        for (const auto& key : { "old_used_utxos", "used_utxos" }) {
+            for (const auto& utxo : details.value(key, nlohmann::json::array())) {
+                utxos.push_back(utxo);
+            }
+        }
+
+        locker_t locker(m_mutex);
+        bool can_sign = json_get_value(details, "error").empty() && !m_watch_only;
+        nlohmann::json inputs = nlohmann::json::array();
+        for (const auto& utxo : utxos) {
+            std::vector<std::string> errors;
+            if (!utxo.contains("txhash") || !utxo.contains("pt_idx") || !utxo.contains("satoshi")) {
+                errors.emplace_back("missing prevout");
+            }
+            if (!json_get_value(utxo, "private_key").empty()) {
+                // Swept input: signed with the given key rather than the user's keys
+                if (json_get_value(utxo, "public_key").empty()) {
+                    errors.emplace_back("missing public key");
+                }
+            } else if (!utxo.contains("subaccount") || !utxo.contains("pointer") || !utxo.contains("script_type")
+                || json_get_value(utxo, "prevout_script").empty()) {
+                errors.emplace_back("missing derivation path");
+            } else if (m_subaccounts.find(utxo.at("subaccount").get<uint32_t>()) == m_subaccounts.end()) {
+                errors.emplace_back("unknown subaccount");
+            }
+            if (is_liquid) {
+                if (!json_get_value(utxo, "error").empty()) {
+                    errors.emplace_back(json_get_value(utxo, "error"));
+                } else if (json_get_value(utxo, "asset_id").empty() || json_get_value(utxo, "abf").empty()
+                    || json_get_value(utxo, "vbf").empty()) {
+                    errors.emplace_back("missing unblinding data");
+                }
+            }
+            can_sign &= errors.empty();
+            inputs.push_back({ { "txhash", json_get_value(utxo, "txhash") }, { "pt_idx", utxo.value("pt_idx", 0u) },
+                { "can_sign", errors.empty() }, { "errors", errors } });
+        }
+
+        nlohmann::json result = { { "can_sign", can_sign && !inputs.empty() }, { "inputs", std::move(inputs) } };
+        if (!json_get_value(details, "error").empty()) {
+            result["error"] = details["error"];
+        } else if (m_watch_only) {
+            result["error"] = "watch-only sessions cannot sign";
+        }
+        return result;
     }
 
     nlohmann::json ga_session::send_transaction(const nlohmann::json& details, const nlohmann::json& twofactor_data)
@@ -3200,9 +5733,36 @@ namespace sdk {
             as_messagepack(private_data).get(), return_tx);
 
         amount::value_type decrease = tx_details.at("limit_decrease");
-        if (decrease != 0) {
+        {
             locker_t locker(m_mutex);
-            update_spending_limits(locker, tx_details["limits"]);
+            if (decrease != 0) {
+                update_spending_limits(locker, tx_details["limits"]);
+            }
+            record_event(locker, "transaction_sent", { { "txhash", tx_details["txhash"] } });
+
+            const auto blinded_outputs_p = details.find("blinded_outputs");
+            if (blinded_outputs_p != details.end()) {
+                // Keep the blinders of our outputs for GA_get_transaction_blinders
+                const std::string txhash = tx_details["txhash"];
+                m_cache.upsert_keyvalue("txblinders" + txhash, nlohmann::json::to_msgpack(*blinded_outputs_p));
+            }
+
+            const auto issuance_p = details.find("issuance");
+            if (issuance_p != details.end()) {
+                // Remember issuances so they can be labelled in the transaction list
+                auto issuances = get_issuances(locker);
+                issuances[tx_details["txhash"].get<std::string>()] = { { "asset_id", issuance_p->at("asset_id") },
+                    { "token_id", issuance_p->at("token_id") }, { "entropy", issuance_p->at("entropy") },
+                    { "contract_hash", issuance_p->at("contract_hash") } };
+                m_cache.upsert_keyvalue("issuances", nlohmann::json::to_msgpack(issuances));
+            }
+
+            // Saves all of the above when any reservations are released. Saving
+            // again is then a no-op, as the cache is only written when changed
+            release_change_addresses(locker, unsigned_tx);
+            if (m_local_encryption_key) {
+                m_cache.save_db(m_local_encryption_key.get());
+            }
         }
 
         // Update the details with the server signed transaction, since it
@@ -3217,9 +5777,18 @@ namespace sdk {
     // Idempotent
     std::string ga_session::broadcast_transaction(const std::string& tx_hex)
     {
+        const uint32_t flags = WALLY_TX_FLAG_USE_WITNESS | (m_net_params.liquid() ? WALLY_TX_FLAG_USE_ELEMENTS : 0);
+        const auto tx = tx_from_hex(tx_hex, flags);
+
         std::string tx_hash;
         wamp_call([&tx_hash](wamp_call_result result) { tx_hash = result.get().argument<std::string>(0); },
             "com.greenaddress.vault.broadcast_raw_tx", tx_hex);
+        locker_t locker(m_mutex);
+        record_event(locker, "transaction_sent", { { "txhash", tx_hash } });
+        release_change_addresses(locker, tx);
+        if (m_local_encryption_key) {
+            m_cache.save_db(m_local_encryption_key.get());
+        }
         return tx_hash;
     }
 
@@ -3237,6 +5806,19 @@ namespace sdk {
             h2b<33>(value_commitment_hex), h2b<32>(abf), h2b<32>(vbf));
     }
 
+    // Derive a blinding factor or key from the master blinding key, so that
+    // a tx blinded with the same inputs and outputs can be reproduced
+    std::array<unsigned char, 32> ga_session::get_deterministic_blinder(
+        byte_span_t output_data, const std::string& type)
+    {
+        std::vector<unsigned char> data(type.begin(), type.end());
+        data.push_back(0); // Separates the type from the data
+        data.insert(data.end(), output_data.begin(), output_data.end());
+
+        locker_t locker(m_mutex);
+        return get_signer().get_deterministic_blinder(data);
+    }
+
     // Idempotent
     void ga_session::send_nlocktimes()
     {
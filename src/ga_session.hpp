@@ -5,6 +5,7 @@
 #include <array>
 #include <chrono>
 #include <map>
+#include <set>
 #include <string>
 #include <thread>
 #include <type_traits>
@@ -160,9 +161,12 @@ namespace sdk {
 
         void set_heartbeat_timeout_handler(heartbeat_t handler);
         void set_ping_fail_handler(ping_fail_t handler);
+        void cancel_pending_requests();
 
         nlohmann::json http_get(const nlohmann::json& params);
         nlohmann::json refresh_assets(const nlohmann::json& params);
+        nlohmann::json lookup_asset(const nlohmann::json& params);
+        void import_blinders(const nlohmann::json& details);
         nlohmann::json validate_asset_domain_name(const nlohmann::json& params);
 
         void register_user(const std::string& mnemonic, bool supports_csv);
@@ -174,9 +178,12 @@ namespace sdk {
             const nlohmann::json& hw_device = nlohmann::json::object());
 
         void register_subaccount_xpubs(const std::vector<std::string>& bip32_xpubs);
+        bool resume_from_snapshot(const std::string& master_xpub);
 
         void login(const std::string& mnemonic, const std::string& password);
         bool login_from_cached(const std::string& mnemonic);
+        void set_cache_encryption_key(const nlohmann::json& details);
+        void change_cache_password(const nlohmann::json& details);
         void login_with_pin(const std::string& pin, const nlohmann::json& pin_data);
         void login_watch_only(const std::string& username, const std::string& password);
         void on_failed_login();
@@ -197,16 +204,26 @@ namespace sdk {
         nlohmann::json get_subaccount(uint32_t subaccount);
         nlohmann::json get_cached_subaccount(uint32_t subaccount) const;
         void rename_subaccount(uint32_t subaccount, const std::string& new_name);
+        void set_subaccount_hidden(uint32_t subaccount, bool hidden);
         uint32_t get_next_subaccount();
         nlohmann::json create_subaccount(const nlohmann::json& details);
         nlohmann::json create_subaccount(const nlohmann::json& details, uint32_t subaccount, const std::string& xpub);
-        nlohmann::json get_receive_address(uint32_t subaccount, const std::string& addr_type_);
+        nlohmann::json get_receive_address(
+            uint32_t subaccount, const std::string& addr_type_, const std::string& source);
         nlohmann::json get_receive_address(const nlohmann::json& details);
+        nlohmann::json get_change_address(uint32_t subaccount);
         std::string get_blinding_key_for_script(const std::string& script_hex);
         void set_local_encryption_key(byte_span_t key);
         std::string blind_address(const std::string& unblinded_addr, const std::string& blinding_key_hex);
         std::string extract_confidential_address(const std::string& blinded_address);
         nlohmann::json get_balance(const nlohmann::json& details);
+        nlohmann::json get_balance_detailed(const nlohmann::json& details);
+        nlohmann::json get_balance_history(const nlohmann::json& details);
+        nlohmann::json get_spending_summary(const nlohmann::json& details);
+        nlohmann::json get_generated_addresses(const nlohmann::json& details);
+        nlohmann::json rescan_transactions(const nlohmann::json& details);
+        nlohmann::json get_sync_status();
+        nlohmann::json export_unblinded_outputs(const nlohmann::json& details);
         nlohmann::json get_available_currencies() const;
         nlohmann::json get_hw_device() const;
         bool is_rbf_enabled() const;
@@ -241,11 +258,33 @@ namespace sdk {
         nlohmann::json get_unspent_outputs(const nlohmann::json& details);
         nlohmann::json get_unspent_outputs_for_private_key(
             const std::string& private_key, const std::string& password, uint32_t unused);
-        nlohmann::json get_transaction_details(const std::string& txhash) const;
+        void freeze_utxo(const nlohmann::json& details);
+        void unfreeze_utxo(const nlohmann::json& details);
+        nlohmann::json get_utxo_policy();
+        void set_utxo_policy(const nlohmann::json& details);
+        nlohmann::json get_transaction_details(const std::string& txhash);
+        nlohmann::json get_transaction_blinders(const std::string& txhash);
+        nlohmann::json decode_transaction(const nlohmann::json& details);
         std::vector<nlohmann::json> get_transactions(uint32_t subaccount, uint32_t page_id);
+        nlohmann::json get_daily_balance_changes(uint32_t subaccount);
+        std::string get_block_date(uint32_t block_height, const std::string& created_at);
+        void for_each_transaction(uint32_t subaccount, const std::function<void(const nlohmann::json&)>& fn);
+        uint32_t for_each_new_transaction(
+            uint32_t subaccount, uint32_t examined_height, const std::function<void(const nlohmann::json&)>& fn);
+        std::map<uint32_t, nlohmann::json> get_address_activity(uint32_t subaccount);
+        nlohmann::json get_previous_address(uint32_t subaccount, uint32_t pointer);
+        void add_address_details(nlohmann::json& address);
+        nlohmann::json get_denylist(locker_t& locker) GDK_REQUIRES(m_mutex);
+        std::set<std::string> get_denied_scripts();
+        nlohmann::json get_address_labels(uint32_t subaccount);
+        nlohmann::json get_transaction_templates(locker_t& locker) GDK_REQUIRES(m_mutex);
+        nlohmann::json apply_transaction_template(const nlohmann::json& details);
+        nlohmann::json add_transfer_addressee(const nlohmann::json& details);
+        void add_local_details(uint32_t subaccount, std::vector<nlohmann::json>& txs);
 
         nlohmann::json create_transaction(const nlohmann::json& details);
         nlohmann::json sign_transaction(const nlohmann::json& details);
+        nlohmann::json can_sign_transaction(const nlohmann::json& details);
         nlohmann::json send_transaction(const nlohmann::json& details, const nlohmann::json& twofactor_data);
         std::string broadcast_transaction(const std::string& tx_hex);
 
@@ -253,6 +292,7 @@ namespace sdk {
         void blind_output(const nlohmann::json& details, const wally_tx_ptr& tx, uint32_t index,
             const nlohmann::json& output, const std::string& asset_commitment_hex,
             const std::string& value_commitment_hex, const std::string& abf, const std::string& vbf);
+        std::array<unsigned char, 32> get_deterministic_blinder(byte_span_t output_data, const std::string& type);
 
         void send_nlocktimes();
         nlohmann::json get_expired_deposits(const nlohmann::json& deposit_details);
@@ -266,6 +306,8 @@ namespace sdk {
         void change_settings_pricing_source(const std::string& currency, const std::string& exchange);
 
         nlohmann::json get_fee_estimates();
+        nlohmann::json estimate_transaction_fee(const nlohmann::json& details) const;
+        nlohmann::json get_fee_bump_options(const nlohmann::json& details) const;
 
         std::string get_mnemonic_passphrase(const std::string& password);
 
@@ -274,7 +316,14 @@ namespace sdk {
         void ack_system_message(const std::string& message);
         void ack_system_message(const std::string& message_hash_hex, const std::string& sig_der_hex);
 
+        nlohmann::json get_message_signing_details(const nlohmann::json& details);
+        nlohmann::json sign_message(const nlohmann::json& details);
+        bool verify_message(const nlohmann::json& details);
+
         nlohmann::json convert_amount(const nlohmann::json& amount_json) const;
+        nlohmann::json parse_amount(const nlohmann::json& amount_details) const;
+        nlohmann::json parse_uri(const nlohmann::json& details) const;
+        nlohmann::json parse_address(const nlohmann::json& details) const;
 
         bool has_blinding_nonce(const std::string& pubkey, const std::string& script);
         void set_blinding_nonce(const std::string& pubkey, const std::string& script, const std::string& nonce);
@@ -282,9 +331,22 @@ namespace sdk {
 
         amount get_min_fee_rate() const;
         amount get_default_fee_rate() const;
+        bool is_fee_estimates_stale(const nlohmann::json& details) const;
         uint32_t get_block_height() const;
         bool have_subaccounts() const;
         amount get_dust_threshold() const;
+        nlohmann::json get_network_details() const;
+        nlohmann::json get_event_journal();
+        nlohmann::json get_cache_stats();
+        nlohmann::json get_denylist();
+        nlohmann::json get_address_labels(const nlohmann::json& details);
+        void set_address_label(const nlohmann::json& details);
+        nlohmann::json get_transaction_templates();
+        void set_transaction_template(const nlohmann::json& details);
+        void set_denylist(const nlohmann::json& denylist);
+        bool is_denied_address(const std::string& address);
+        bool is_denied_script(byte_span_t script);
+        std::pair<std::set<std::string>, std::set<std::string>> get_known_scripts(uint32_t subaccount);
         nlohmann::json get_spending_limits() const;
         bool is_spending_limits_decrease(const nlohmann::json& details);
         const network_parameters& get_network_parameters() const { return m_net_params; }
@@ -323,6 +385,10 @@ namespace sdk {
         void authenticate(locker_t& locker, const std::string& sig_der_hex, const std::string& path_hex,
             const std::string& device_id, const nlohmann::json& hw_device) GDK_REQUIRES(m_mutex);
         void login(locker_t& locker, const std::string& mnemonic) GDK_REQUIRES(m_mutex);
+        void register_subaccount_xpubs(locker_t& locker, const std::vector<std::string>& bip32_xpubs)
+            GDK_REQUIRES(m_mutex);
+        nlohmann::json get_session_snapshot(locker_t& locker) GDK_REQUIRES(m_mutex);
+        void save_session_snapshot(locker_t& locker, const nlohmann::json& snapshot) GDK_REQUIRES(m_mutex);
         void set_notification_handler(locker_t& locker, GA_notification_handler handler, void* context)
             GDK_REQUIRES(m_mutex);
 
@@ -338,11 +404,34 @@ namespace sdk {
         void update_spending_limits(locker_t& locker, const nlohmann::json& limits) GDK_REQUIRES(m_mutex);
         nlohmann::json get_spending_limits(locker_t& locker) const GDK_REQUIRES(m_mutex);
         nlohmann::json get_subaccount(locker_t& locker, uint32_t subaccount) GDK_REQUIRES(m_mutex);
+        std::set<uint32_t> get_hidden_subaccounts(locker_t& locker) GDK_REQUIRES(m_mutex);
+        nlohmann::json get_cached_assets(locker_t& locker, const std::set<std::string>& asset_ids)
+            GDK_REQUIRES(m_mutex);
+        nlohmann::json get_assets_metadata(locker_t& locker, const nlohmann::json& satoshi) GDK_REQUIRES(m_mutex);
+        nlohmann::json get_imported_blinders(locker_t& locker) GDK_REQUIRES(m_mutex);
+        nlohmann::json get_issuances(locker_t& locker) GDK_REQUIRES(m_mutex);
+        void add_transaction_io(locker_t& locker, const wally_tx_ptr& tx, nlohmann::json& result)
+            GDK_REQUIRES(m_mutex);
+        void mark_conflicted_transactions(std::vector<nlohmann::json>& tx_list,
+            const std::map<std::string, std::set<std::string>>& unconfirmed_spends, bool is_first_page,
+            bool is_last_page);
+        bool unblind_utxo_with_imported_blinders(nlohmann::json& utxo);
+        void load_cache(locker_t& locker, uint32_t type) GDK_REQUIRES(m_mutex);
+        nlohmann::json get_frozen_utxos(locker_t& locker) GDK_REQUIRES(m_mutex);
+        void set_utxo_frozen(const nlohmann::json& details, bool is_frozen);
+        nlohmann::json get_utxo_policy(locker_t& locker) GDK_REQUIRES(m_mutex);
+        nlohmann::json get_change_reservations(locker_t& locker) GDK_REQUIRES(m_mutex);
+        nlohmann::json get_address_labels(locker_t& locker, uint32_t subaccount) GDK_REQUIRES(m_mutex);
+        void set_change_reservations(locker_t& locker, const nlohmann::json& reservations) GDK_REQUIRES(m_mutex);
+        void release_change_addresses(locker_t& locker, const wally_tx_ptr& tx) GDK_REQUIRES(m_mutex);
+        void flag_suspicious_utxos(locker_t& locker, nlohmann::json& utxos) GDK_REQUIRES(m_mutex);
         nlohmann::json get_subaccount_balance_from_server(uint32_t subaccount, uint32_t num_confs);
         nlohmann::json convert_amount(locker_t& locker, const nlohmann::json& amount_json) const GDK_REQUIRES(m_mutex);
         nlohmann::json convert_fiat_cents(locker_t& locker, amount::value_type fiat_cents) const GDK_REQUIRES(m_mutex);
         nlohmann::json get_settings(locker_t& locker) GDK_REQUIRES(m_mutex);
         void unblind_utxo(nlohmann::json& utxo, const std::string& policy_asset);
+        void unblind_utxos(const std::vector<nlohmann::json*>& utxos, const std::string& policy_asset);
+        void add_sync_progress(const std::string& counter, uint32_t n);
         nlohmann::json cleanup_utxos(nlohmann::json& utxos, const std::string& policy_asset);
 
         autobahn::wamp_subscription subscribe(
@@ -367,6 +456,10 @@ namespace sdk {
             GDK_REQUIRES(m_mutex);
 
         nlohmann::json set_fee_estimates(locker_t& locker, const nlohmann::json& fee_estimates) GDK_REQUIRES(m_mutex);
+        uint64_t get_fee_estimates_age(locker_t& locker) const GDK_REQUIRES(m_mutex);
+
+        void record_event(locker_t& locker, const std::string& event_type, nlohmann::json details)
+            GDK_REQUIRES(m_mutex);
 
         nlohmann::json refresh_http_data(const std::string& type, bool refresh);
 
@@ -420,21 +513,31 @@ namespace sdk {
         template <typename F, typename... Args>
         void wamp_call(F&& body, const std::string& method_name, Args&&... args) const
         {
-            constexpr uint8_t timeout = 10;
+            const uint32_t cancel_id = m_cancel_id;
             autobahn::wamp_call_options call_options;
-            call_options.set_timeout(std::chrono::seconds(timeout));
-            auto fn = m_session->call(method_name, std::make_tuple(std::forward<Args>(args)...), call_options)
-                          .then(boost::launch::async, std::forward<F>(body));
-            for (;;) {
-                const auto status = fn.wait_for(boost::chrono::seconds(timeout));
-                if (status == boost::future_status::timeout && !is_connected()) {
-                    throw timeout_error{};
-                }
+            call_options.set_timeout(std::chrono::seconds(m_request_timeout));
+            auto fn = m_session->call(method_name, std::make_tuple(std::forward<Args>(args)...), call_options);
+            for (uint32_t waited = 1;; ++waited) {
+                // Poll every second so that cancellation is noticed promptly
+                const auto status = fn.wait_for(boost::chrono::seconds(1));
                 if (status == boost::future_status::ready) {
                     break;
                 }
+                check_cancelled(cancel_id);
+                if (waited % m_request_timeout == 0 && !is_connected()) {
+                    throw timeout_error{};
+                }
+            }
+            // Run the handler on the calling thread: a cancelled call may still
+            // complete later and must not touch the callers stack
+            std::forward<F>(body)(std::move(fn));
+        }
+
+        void check_cancelled(uint32_t cancel_id) const
+        {
+            if (m_cancel_id != cancel_id) {
+                throw cancelled_error{};
             }
-            fn.get();
         }
 
         std::vector<unsigned char> get_pin_password(const std::string& pin, const std::string& pin_identifier);
@@ -480,6 +583,8 @@ namespace sdk {
 
         network_control_context m_network_control;
         boost::asio::thread_pool m_pool{ 4 };
+        // Shared by all unblinding batches, which are CPU bound
+        boost::asio::thread_pool m_unblind_pool{ std::max(1u, std::thread::hardware_concurrency()) };
 
         GA_notification_handler m_notification_handler GDK_GUARDED_BY(m_mutex);
         void* m_notification_context GDK_PT_GUARDED_BY(m_mutex);
@@ -487,6 +592,12 @@ namespace sdk {
         nlohmann::json m_login_data GDK_GUARDED_BY(m_mutex);
         boost::optional<std::array<unsigned char, PBKDF2_HMAC_SHA512_LEN>> m_local_encryption_key GDK_GUARDED_BY(
             m_mutex);
+        // Host provided key to use in place of m_local_encryption_key, if any
+        boost::optional<std::array<unsigned char, PBKDF2_HMAC_SHA512_LEN>> m_cache_encryption_key GDK_GUARDED_BY(
+            m_mutex);
+        // User password mixed into the key above (or the wallet derived key) to get m_local_encryption_key
+        std::string m_cache_password GDK_GUARDED_BY(m_mutex);
+        boost::optional<std::array<unsigned char, PBKDF2_HMAC_SHA512_LEN>> m_cache_base_key GDK_GUARDED_BY(m_mutex);
         std::array<uint32_t, 32> m_gait_path GDK_GUARDED_BY(m_mutex);
         nlohmann::json m_limits_data GDK_GUARDED_BY(m_mutex);
         nlohmann::json m_twofactor_config GDK_GUARDED_BY(m_mutex);
@@ -495,6 +606,8 @@ namespace sdk {
         std::string m_fiat_source GDK_GUARDED_BY(m_mutex);
         std::string m_fiat_rate GDK_GUARDED_BY(m_mutex);
         std::string m_fiat_currency GDK_GUARDED_BY(m_mutex);
+        uint64_t m_fiat_rate_updated GDK_GUARDED_BY(m_mutex); // Seconds since the epoch, 0 if no rate
+        nlohmann::json m_sync_status GDK_GUARDED_BY(m_mutex); // History fetching and unblinding counters
         uint64_t m_earliest_block_time GDK_GUARDED_BY(m_mutex);
 
         nlohmann::json m_assets;
@@ -505,6 +618,7 @@ namespace sdk {
         std::unique_ptr<ga_user_pubkeys> m_recovery_pubkeys GDK_PT_GUARDED_BY(m_mutex);
         uint32_t m_next_subaccount GDK_GUARDED_BY(m_mutex);
         std::vector<uint32_t> m_fee_estimates GDK_GUARDED_BY(m_mutex);
+        uint64_t m_fee_estimates_updated GDK_GUARDED_BY(m_mutex); // Seconds since the epoch, 0 if never
         uint32_t m_block_height GDK_GUARDED_BY(m_mutex);
         std::unique_ptr<signer> m_signer GDK_PT_GUARDED_BY(m_mutex);
 
@@ -524,6 +638,8 @@ namespace sdk {
         std::string m_last_tor_socks5;
         cache m_cache GDK_GUARDED_BY(m_mutex);
         const std::string m_user_agent;
+        const uint32_t m_request_timeout;
+        std::atomic<uint32_t> m_cancel_id{ 0 };
     };
 
 } // namespace sdk
@@ -1,6 +1,8 @@
 #include <algorithm>
 #include <array>
 #include <ctime>
+#include <limits>
+#include <map>
 #include <string>
 #include <vector>
 
@@ -97,9 +99,41 @@ namespace sdk {
                 }
             }
 
+            const auto issuance_p = utxo.find("issuance");
+            if (issuance_p != utxo.end()) {
+                // This input issues a new asset: the issuance amounts are explicit, and
+                // a new issuance has a zero nonce and the contract hash as its entropy
+                const auto asset_amount = tx_confidential_value_from_satoshi(issuance_p->at("asset_satoshi"));
+                const uint64_t token_satoshi = issuance_p->at("token_satoshi");
+                std::vector<unsigned char> token_amount;
+                if (token_satoshi != 0) {
+                    const auto value = tx_confidential_value_from_satoshi(token_satoshi);
+                    token_amount.assign(std::begin(value), std::end(value));
+                }
+                const auto contract_hash = h2b_rev(issuance_p->at("contract_hash"));
+                tx_elements_input_issuance_set(
+                    tx, tx->num_inputs - 1, abf_t{ { 0 } }, contract_hash, asset_amount, token_amount);
+            }
+
             return amount(utxo.at("satoshi"));
         }
 
+        // Return the asset ids and amounts issued by a utxo. These act as explicit
+        // inputs of the new asset and its reissuance token when blinding
+        static std::vector<std::pair<std::string, uint64_t>> get_issued_amounts(const nlohmann::json& utxo)
+        {
+            std::vector<std::pair<std::string, uint64_t>> issued;
+            const auto issuance_p = utxo.find("issuance");
+            if (issuance_p != utxo.end()) {
+                issued.emplace_back(issuance_p->at("asset_id"), issuance_p->at("asset_satoshi"));
+                const uint64_t token_satoshi = issuance_p->at("token_satoshi");
+                if (token_satoshi != 0) {
+                    issued.emplace_back(issuance_p->at("token_id"), token_satoshi);
+                }
+            }
+            return issued;
+        }
+
         static void calculate_input_subtype(nlohmann::json& utxo, const wally_tx_ptr& tx, size_t i)
         {
             // Calculate the subtype of a tx input we wish to present as a utxo.
@@ -140,6 +174,302 @@ namespace sdk {
             std::copy(reordered_inputs.begin(), reordered_inputs.end(), in_p);
         }
 
+        // Split the amount being sent between the addressees in proportion to their
+        // weights, giving any remainder from rounding to the first addressee.
+        // The addressees outputs must be the first outputs of the tx
+        static void split_send_all(nlohmann::json& result, const wally_tx_ptr& tx, amount total, amount dust_threshold)
+        {
+            auto& addressees = result.at("addressees");
+            // OP_RETURN addressees carry no value, and a lone paying addressee needs no weight
+            const auto get_weight = [](const nlohmann::json& addressee) {
+                return addressee.contains("op_return") ? 0u : json_get_value(addressee, "weight", 1u);
+            };
+            uint64_t total_weight = 0;
+            for (const auto& addressee : addressees) {
+                total_weight += get_weight(addressee);
+            }
+            GDK_RUNTIME_ASSERT(total_weight <= std::numeric_limits<uint32_t>::max());
+            if (total_weight == 0) {
+                return; // Only OP_RETURN addressees, already flagged as an error
+            }
+
+            // Computed so as not to overflow: weight and total_weight fit in 32 bits
+            const amount::value_type value = total.value();
+            amount::value_type remainder = value;
+            std::vector<amount::value_type> shares;
+            for (const auto& addressee : addressees) {
+                const uint32_t weight = get_weight(addressee);
+                const auto share = value / total_weight * weight + value % total_weight * weight / total_weight;
+                shares.emplace_back(share);
+                remainder -= share;
+            }
+            const auto first_paid = std::find_if(
+                addressees.begin(), addressees.end(), [](const auto& a) { return !a.contains("op_return"); });
+            shares.at(std::distance(addressees.begin(), first_paid)) += remainder;
+
+            for (size_t i = 0; i < shares.size(); ++i) {
+                if (addressees[i].contains("op_return")) {
+                    continue;
+                }
+                if (shares[i] < dust_threshold.value()) {
+                    set_tx_error(result, res::id_insufficient_funds); // Insufficient funds
+                }
+                tx->outputs[i].satoshi = shares[i];
+                addressees[i]["satoshi"] = shares[i];
+            }
+        }
+
+        // Caller provided UTXOs must be unspent outputs of the wallet. Replace them with
+        // the wallet's own copies so that their values and assets cannot be altered.
+        // Note this fetches the wallet's UTXOs on every call that passes them back
+        static void validate_utxos(ga_session& session, nlohmann::json& result, uint32_t subaccount)
+        {
+            const auto wallet_utxos
+                = session.get_unspent_outputs({ { "subaccount", subaccount }, { "num_confs", 0 } });
+            std::map<std::pair<std::string, uint32_t>, nlohmann::json> by_outpoint;
+            for (const auto& asset_utxos : wallet_utxos.items()) {
+                if (asset_utxos.key() == "error") {
+                    continue; // Outputs we failed to unblind can't be spent
+                }
+                for (const auto& utxo : asset_utxos.value()) {
+                    auto key = std::make_pair<std::string, uint32_t>(utxo.at("txhash"), utxo.at("pt_idx"));
+                    by_outpoint.emplace(std::move(key), utxo);
+                }
+            }
+
+            const auto replace_utxos = [&by_outpoint, &result](nlohmann::json& utxos) {
+                for (auto& utxo : utxos) {
+                    const auto p
+                        = by_outpoint.find(std::make_pair<std::string, uint32_t>(utxo.at("txhash"), utxo.at("pt_idx")));
+                    if (p == by_outpoint.end()) {
+                        set_tx_error(result, res::id_no_utxos_found);
+                        return false;
+                    }
+                    utxo = p->second;
+                }
+                return true;
+            };
+
+            // The "error" group holds outputs we failed to unblind, which can't be spent
+            auto& utxos = result["utxos"];
+            utxos.erase("error");
+            for (auto& asset_utxos : utxos) {
+                if (!replace_utxos(asset_utxos)) {
+                    return;
+                }
+            }
+            const auto used_utxos_p = result.find("used_utxos");
+            if (used_utxos_p != result.end()) {
+                replace_utxos(*used_utxos_p);
+            }
+        }
+
+        // Utxos flagged by the wallet's utxo policy are kept in their own section, and
+        // are only made available for selection when the caller asks to spend them
+        static void arrange_suspicious_utxos(ga_session& session, nlohmann::json& result)
+        {
+            auto& utxos = result.at("utxos");
+            nlohmann::json suspicious = utxos.value("suspicious", nlohmann::json::array());
+            utxos.erase("suspicious");
+            for (auto& asset_utxos : utxos.items()) {
+                if (asset_utxos.key() == "error") {
+                    continue;
+                }
+                auto& values = asset_utxos.value();
+                for (auto p = values.begin(); p != values.end();) {
+                    if (p->contains("suspicious_reason")) {
+                        suspicious.emplace_back(std::move(*p));
+                        p = values.erase(p);
+                    } else {
+                        ++p;
+                    }
+                }
+            }
+
+            if (!json_get_value(result, "spend_suspicious_utxos", false)) {
+                if (!suspicious.empty()) {
+                    utxos["suspicious"] = std::move(suspicious);
+                }
+                const auto& used_utxos = result.value("used_utxos", nlohmann::json::array());
+                if (std::any_of(used_utxos.begin(), used_utxos.end(),
+                        [](const auto& utxo) { return utxo.contains("suspicious_reason"); })) {
+                    set_tx_error(result, "spending suspicious utxos requires spend_suspicious_utxos");
+                }
+                return;
+            }
+            for (auto& utxo : suspicious) {
+                utxos[session.asset_id_from_string(utxo.value("asset_id", std::string{}))].emplace_back(
+                    std::move(utxo));
+            }
+        }
+
+        // Fixed fee rates for building transactions without current fee estimates.
+        // These are conservative, i.e. chosen to confirm rather than to minimize fees
+        static uint32_t get_preset_fee_rate(ga_session& session, const std::string& preset)
+        {
+            static const std::map<std::string, uint32_t> PRESET_FEE_RATES
+                = { { "low", 5000 }, { "medium", 20000 }, { "high", 50000 } };
+            const auto p = PRESET_FEE_RATES.find(preset);
+            GDK_RUNTIME_ASSERT_MSG(p != PRESET_FEE_RATES.end(), "Unknown fee preset");
+
+            const uint32_t min_fee_rate = session.get_min_fee_rate().value();
+            if (session.get_network_parameters().liquid()) {
+                return min_fee_rate; // Liquid blocks are rarely full
+            }
+            return std::max(p->second, min_fee_rate);
+        }
+
+        // Flag addressees that are the wallet's own addresses, or that this subaccount has paid
+        // before, so that callers can warn about likely mistakes. These flags are advisory only
+        static void flag_known_addressees(ga_session& session, nlohmann::json& result, uint32_t subaccount)
+        {
+            const auto known = session.get_known_scripts(subaccount);
+            const auto& net_params = session.get_network_parameters();
+            for (auto& addressee : result.at("addressees")) {
+                std::string script = json_get_value(addressee, "scriptpubkey");
+                if (script.empty()) {
+                    script = b2h(scriptpubkey_from_address(net_params, addressee.at("address")));
+                }
+                addressee["is_own_address"] = known.first.count(script) != 0;
+                addressee["is_previously_paid"] = known.second.count(script) != 0;
+            }
+        }
+
+        // Select the UTXOs to consolidate: all those below a value threshold, and all those
+        // paid to addresses holding more than a given number of UTXOs. They are then spent
+        // in a re-deposit, by default at the minimum fee rate
+        static void select_consolidation_utxos(ga_session& session, nlohmann::json& result, uint32_t subaccount)
+        {
+            const auto& consolidate = result.at("consolidate");
+            const uint64_t max_satoshi = json_get_value(consolidate, "max_satoshi", uint64_t{ 0 });
+            const uint32_t max_per_address = json_get_value(consolidate, "max_utxos_per_address", 0u);
+            GDK_RUNTIME_ASSERT_MSG(max_satoshi != 0 || max_per_address != 0, "No consolidation criteria given");
+
+            const auto wallet_utxos
+                = session.get_unspent_outputs({ { "subaccount", subaccount }, { "num_confs", 0 } });
+            const auto utxos_p = wallet_utxos.find("btc");
+            const auto& utxos = utxos_p == wallet_utxos.end() ? nlohmann::json::array() : *utxos_p;
+
+            std::map<std::pair<uint32_t, uint32_t>, size_t> per_address;
+            for (const auto& utxo : utxos) {
+                if (json_get_value(utxo, "is_frozen", false)) {
+                    continue; // Frozen coins are never consolidated
+                }
+                ++per_address[std::make_pair<uint32_t, uint32_t>(utxo.at("pointer"), utxo.at("script_type"))];
+            }
+
+            std::vector<nlohmann::json> selected;
+            for (const auto& utxo : utxos) {
+                if (json_get_value(utxo, "is_frozen", false)) {
+                    continue;
+                }
+                const uint64_t satoshi = utxo.at("satoshi");
+                const auto count
+                    = per_address[std::make_pair<uint32_t, uint32_t>(utxo.at("pointer"), utxo.at("script_type"))];
+                if ((max_satoshi != 0 && satoshi < max_satoshi) || (max_per_address != 0 && count > max_per_address)) {
+                    selected.emplace_back(utxo);
+                }
+            }
+            if (selected.size() < 2u) {
+                set_tx_error(result, res::id_no_utxos_found); // Nothing worth consolidating
+            }
+
+            result["utxos"] = { { "btc", selected } };
+            result["used_utxos"] = selected;
+            result["utxo_strategy"] = UTXO_SEL_MANUAL;
+            result["is_redeposit"] = true;
+            if (result.find("fee_rate") == result.end()) {
+                result["fee_rate"] = session.get_min_fee_rate().value();
+            }
+        }
+
+        // Report the fee paid to consolidate versus the fees saved by not having
+        // to spend the consolidated UTXOs individually at a future fee rate.
+        // The savings are approximated by treating the tx size as all input data
+        static void add_consolidation_info(ga_session& session, nlohmann::json& result)
+        {
+            const uint64_t num_utxos = result.at("used_utxos").size();
+            const uint64_t vsize = result.at("transaction_vsize");
+            const uint64_t fee = result.at("fee");
+            auto& consolidate = result["consolidate"];
+            const uint64_t future_fee_rate
+                = json_add_if_missing(consolidate, "future_fee_rate", session.get_default_fee_rate().value());
+            const uint64_t future_savings = (num_utxos - 1) * (vsize / num_utxos) * future_fee_rate / 1000;
+            consolidate["num_utxos"] = num_utxos;
+            consolidate["fee"] = fee;
+            consolidate["future_savings"] = future_savings;
+            consolidate["net_savings"] = static_cast<int64_t>(future_savings) - static_cast<int64_t>(fee);
+        }
+
+        // Set up the issuance of a new asset. The first L-BTC utxo issues the asset
+        // (and optionally its reissuance token), which are paid to the given addresses
+        static void prepare_issuance(ga_session& session, nlohmann::json& result)
+        {
+            auto& issuance = result.at("issuance");
+
+            // Remove any issuance data from a previous call
+            auto& btc_utxos = result.at("utxos")["btc"];
+            for (auto& utxo : btc_utxos) {
+                utxo.erase("issuance");
+            }
+            std::vector<nlohmann::json> addressees;
+            for (const auto& addressee : result.value("addressees", nlohmann::json::array())) {
+                if (!json_get_value(addressee, "is_issuance", false)) {
+                    addressees.emplace_back(addressee);
+                }
+            }
+            result["addressees"] = addressees;
+
+            if (json_get_value(result, "send_all", false)
+                || json_get_value(result, "utxo_strategy", UTXO_SEL_DEFAULT) != UTXO_SEL_DEFAULT) {
+                set_tx_error(result, "issuance requires default utxo selection without send all");
+                return;
+            }
+            // Frozen coins are never used to issue from
+            const auto utxo_p = std::find_if(btc_utxos.begin(), btc_utxos.end(),
+                [](const auto& utxo) { return !json_get_value(utxo, "is_frozen", false); });
+            if (utxo_p == btc_utxos.end()) {
+                set_tx_error(result, res::id_insufficient_funds);
+                return;
+            }
+
+            const uint64_t asset_satoshi = issuance.at("asset_satoshi");
+            const uint64_t token_satoshi = json_get_value(issuance, "token_satoshi", uint64_t{ 0 });
+            const std::string zero_hash(SHA256_LEN * 2, '0');
+            const std::string contract_hash = json_add_if_missing(issuance, "contract_hash", zero_hash);
+            GDK_RUNTIME_ASSERT_MSG(h2b(contract_hash).size() == SHA256_LEN, "invalid contract_hash");
+            if (asset_satoshi == 0) {
+                set_tx_error(result, res::id_invalid_amount);
+                return;
+            }
+
+            auto& utxo = *utxo_p;
+            const auto entropy = tx_elements_issuance_generate_entropy(
+                h2b_rev(utxo.at("txhash")), utxo.at("pt_idx"), h2b_rev(contract_hash));
+            const std::string asset_id = b2h_rev(tx_elements_issuance_calculate_asset(entropy));
+            const std::string token_id = b2h_rev(tx_elements_issuance_calculate_reissuance_token(entropy));
+
+            issuance["asset_id"] = asset_id;
+            issuance["token_id"] = token_id;
+            issuance["entropy"] = b2h_rev(entropy);
+            issuance["txhash"] = utxo.at("txhash");
+            issuance["pt_idx"] = utxo.at("pt_idx");
+            utxo["issuance"] = { { "asset_id", asset_id }, { "asset_satoshi", asset_satoshi }, { "token_id", token_id },
+                { "token_satoshi", token_satoshi }, { "contract_hash", contract_hash } };
+
+            // Pay the issued amounts before any other addressees
+            std::vector<nlohmann::json> issued;
+            issued.push_back({ { "address", issuance.at("asset_address") }, { "satoshi", asset_satoshi },
+                { "asset_tag", asset_id }, { "is_issuance", true } });
+            if (token_satoshi != 0) {
+                issued.push_back({ { "address", issuance.at("token_address") }, { "satoshi", token_satoshi },
+                    { "asset_tag", token_id }, { "is_issuance", true } });
+            }
+            addressees.insert(addressees.begin(), std::begin(issued), std::end(issued));
+            result["addressees"] = addressees;
+            result["addressees_read_only"] = true;
+        }
+
         // Check if a tx to bump is present, and if so add the details required to bump it
         static std::pair<bool, bool> check_bump_tx(ga_session& session, nlohmann::json& result, uint32_t subaccount)
         {
@@ -191,7 +521,7 @@ namespace sdk {
                 // so that miners are incentivized to mine both together).
                 const amount new_fee_rate = amount(result.at("fee_rate"));
                 const auto new_fee = get_tx_fee(tx, min_fee_rate, new_fee_rate);
-                const amount network_fee = new_fee <= old_fee ? amount() : new_fee;
+                const amount network_fee = new_fee <= old_fee ? amount() : new_fee - old_fee;
                 result["network_fee"] = network_fee.value();
             }
 
@@ -328,12 +658,23 @@ namespace sdk {
             bool is_rbf, is_cpfp;
             std::tie(is_rbf, is_cpfp) = check_bump_tx(session, result, subaccount);
 
+            const bool is_consolidation = result.find("consolidate") != result.end();
+            if (is_consolidation) {
+                GDK_RUNTIME_ASSERT_MSG(!is_rbf && !is_cpfp, "Consolidation can not be fee-bumped");
+                if (net_params.liquid()) {
+                    set_tx_error(result, "consolidation not supported for liquid");
+                    return;
+                }
+                select_consolidation_utxos(session, result, subaccount);
+            }
+
             const bool is_redeposit = json_get_value(result, "is_redeposit", false);
 
             if (is_redeposit) {
                 if (result.find("addressees") == result.end()) {
                     // For re-deposit/CPFP, create the addressee if not present already
-                    const auto address = session.get_receive_address(subaccount, {}).at("address");
+                    const auto address
+                        = session.get_receive_address(subaccount, {}, "create_transaction").at("address");
                     std::vector<nlohmann::json> addressees;
                     addressees.emplace_back(nlohmann::json({ { "address", address }, { "satoshi", 0 } }));
                     result["addressees"] = addressees;
@@ -385,7 +726,8 @@ namespace sdk {
                     addressees_p->at(0)["satoshi"] = 0;
                 } else {
                     // Send to an address in the current subaccount
-                    const auto address = session.get_receive_address(subaccount, {}).at("address");
+                    const auto address
+                        = session.get_receive_address(subaccount, {}, "create_transaction").at("address");
                     std::vector<nlohmann::json> addressees;
                     addressees.emplace_back(nlohmann::json({ { "address", address }, { "satoshi", 0 } }));
                     result["addressees"] = addressees;
@@ -394,13 +736,18 @@ namespace sdk {
             }
 
             const bool confidential_utxos_only = json_add_if_missing(result, "confidential_utxos_only", false);
-            if (!is_sweep && result.find("utxos") == result.end()) {
+            if (!is_sweep && result.find("utxos") != result.end()) {
+                validate_utxos(session, result, subaccount);
+            } else if (!is_sweep) {
                 // Fetch the users utxos from the current subaccount.
                 // if RBF/cpfp, require 1 confirmation.
                 const uint32_t num_confs = (is_rbf || is_cpfp) ? 1 : 0;
                 result["utxos"] = session.get_unspent_outputs(nlohmann::json({ { "subaccount", subaccount },
                     { "num_confs", num_confs }, { "confidential", confidential_utxos_only } }));
             }
+            if (!is_sweep) {
+                arrange_suspicious_utxos(session, result);
+            }
 
             const bool send_all = json_add_if_missing(result, "send_all", false);
             // For now, the amount can't be directly edited for the below actions
@@ -408,6 +755,16 @@ namespace sdk {
             // selected/deselected
             result["amount_read_only"] = send_all || is_redeposit || is_rbf || is_cpfp || is_sweep;
 
+            const bool is_issuance = result.contains("issuance");
+            if (is_issuance) {
+                if (!is_liquid || is_rbf || is_cpfp || is_redeposit || is_consolidation || is_sweep) {
+                    set_tx_error(result, "issuance not supported for this transaction");
+                    return;
+                }
+                prepare_issuance(session, result);
+                addressees_p = result.find("addressees");
+            }
+
             const std::string strategy = json_add_if_missing(result, "utxo_strategy", UTXO_SEL_DEFAULT);
             const bool manual_selection = strategy == UTXO_SEL_MANUAL;
             GDK_RUNTIME_ASSERT(strategy == UTXO_SEL_DEFAULT || manual_selection);
@@ -429,8 +786,19 @@ namespace sdk {
             // Send all should not be visible/set when RBFing
             GDK_RUNTIME_ASSERT(!is_rbf || (!send_all || is_redeposit));
 
-            if (send_all && num_addressees > 1) {
-                set_tx_error(result, res::id_send_all_requires_a_single); // Send all requires a single output
+            if (send_all && num_addressees) {
+                // Sending everything to several addressees requires a weight to split it by.
+                // OP_RETURN addressees are always paid nothing, so don't need one
+                const auto num_paid = std::count_if(addressees_p->begin(), addressees_p->end(),
+                    [](const auto& addressee) { return !addressee.contains("op_return"); });
+                const bool have_weights = num_paid == 1
+                    || (!is_liquid && num_paid > 1
+                        && std::all_of(addressees_p->begin(), addressees_p->end(), [](const auto& addressee) {
+                               return addressee.contains("op_return") || json_get_value(addressee, "weight", 0u) != 0;
+                           }));
+                if (!have_weights) {
+                    set_tx_error(result, res::id_send_all_requires_a_single); // Send all requires a single output
+                }
             }
 
             auto& utxos = result.at("utxos");
@@ -478,7 +846,10 @@ namespace sdk {
                         }
                     }
 
-                    asset_tags.insert(session.asset_id_from_string(addressee.value("asset_tag", "btc")));
+                    if (!json_get_value(addressee, "is_issuance", false)) {
+                        // Issued assets are paid from the issuance rather than from utxos
+                        asset_tags.insert(session.asset_id_from_string(addressee.value("asset_tag", "btc")));
+                    }
                 }
             }
             result["addressees_have_assets"] = addressees_have_assets;
@@ -487,6 +858,9 @@ namespace sdk {
                 const bool include_fee = asset_tag == "btc";
 
                 std::vector<nlohmann::json> current_used_utxos;
+                // Unfrozen utxos not spent by the initial selection, in the order to add them
+                std::vector<nlohmann::json> spare_utxos;
+                size_t next_spare_utxo = 0;
                 amount available_total, total, fee, v;
 
                 if (is_rbf) {
@@ -528,12 +902,18 @@ namespace sdk {
                     const auto asset_utxos_p = utxos.find(asset_tag);
                     if (asset_utxos_p != utxos.end()) {
                         for (auto& utxo : utxos.at(asset_tag)) {
-                            if (send_all || total < required_total) {
+                            if (json_get_value(utxo, "is_frozen", false)) {
+                                // Frozen coins are only spent if manually selected
+                                continue;
+                            }
+                            // The utxo issuing an asset must always be spent
+                            if (send_all || total < required_total || utxo.contains("issuance")) {
                                 v = add_utxo(session, tx, utxo);
                                 total += v;
                                 current_used_utxos.emplace_back(utxo);
                             } else {
                                 v = static_cast<amount::value_type>(utxo.at("satoshi"));
+                                spare_utxos.emplace_back(utxo);
                             }
                             available_total += v;
                         }
@@ -559,7 +939,18 @@ namespace sdk {
                 }
 
                 if (result.find("fee_rate") == result.end()) {
-                    result["fee_rate"] = session.get_default_fee_rate().value();
+                    const std::string preset = json_get_value(result, "fee_preset");
+                    if (!preset.empty()) {
+                        result["fee_rate"] = get_preset_fee_rate(session, preset);
+                    } else {
+                        result["fee_rate"] = session.get_default_fee_rate().value();
+                        // Warn if the default fee rate comes from old estimates, e.g. when offline
+                        result["fee_estimates_stale"] = session.is_fee_estimates_stale(result);
+                    }
+                }
+                if (json_get_value(result, "fee_estimates_stale", false)
+                    && json_get_value(result, "require_fresh_fee_estimates", false)) {
+                    set_tx_error(result, "Fee estimates are out of date");
                 }
                 const amount dust_threshold = session.get_dust_threshold();
                 const amount user_fee_rate = amount(result.at("fee_rate"));
@@ -618,6 +1009,8 @@ namespace sdk {
                             required_total = available_total - fee;
                             if (is_liquid) {
                                 set_tx_output_commitment(net_params, tx, 0, asset_tag, required_total.value());
+                            } else if (num_addressees > 1u) {
+                                split_send_all(result, tx, required_total, dust_threshold);
                             } else {
                                 tx->outputs[0].satoshi = required_total.value();
                             }
@@ -633,15 +1026,14 @@ namespace sdk {
                         // We don't have enough funds to cover the fee yet, or we
                         // need to add more to avoid a dusty change output
                         force_add_utxo = false;
-                        if (manual_selection || utxos.empty()
-                            || current_used_utxos.size() == utxos.at(asset_tag).size()) {
+                        if (manual_selection || next_spare_utxo == spare_utxos.size()) {
                             // Used all inputs and do not have enough funds
                             set_tx_error(result, res::id_insufficient_funds); // Insufficient funds
                             goto leave_loop;
                         }
 
                         // FIXME: Use our strategy here when non-default implemented
-                        auto& utxo = utxos.at(asset_tag).at(current_used_utxos.size());
+                        const auto& utxo = spare_utxos.at(next_spare_utxo++);
                         total += add_utxo(session, tx, utxo);
                         current_used_utxos.emplace_back(utxo);
                         continue;
@@ -690,7 +1082,7 @@ namespace sdk {
                         // Find out where to send any change
                         const uint32_t change_subaccount = result.value("change_subaccount", subaccount);
                         result["change_subaccount"] = change_subaccount;
-                        auto change_address = session.get_receive_address(change_subaccount, {});
+                        auto change_address = session.get_change_address(change_subaccount);
                         if (is_liquid) {
                             // set a temporary blinding key, will be changed later through the resolvers. we need
                             // to have one because all our create_transaction logic relies on being able to blind
@@ -752,7 +1144,10 @@ namespace sdk {
                     set_tx_output_commitment(net_params, tx, fee_index, asset_tag, fee.value());
                 }
 
-                if (required_total == 0 && (!include_fee || !is_liquid)) {
+                const bool has_op_return = num_addressees
+                    && std::any_of(addressees_p->begin(), addressees_p->end(),
+                        [](const auto& addressee) { return addressee.contains("op_return"); });
+                if (required_total == 0 && !has_op_return && (!include_fee || !is_liquid)) {
                     set_tx_error(result, res::id_no_amount_specified); // // No amount specified
                 } else if (user_fee_rate < min_fee_rate) {
                     set_tx_error(
@@ -778,8 +1173,24 @@ namespace sdk {
                         set_tx_error(result, res::id_invalid_replacement_fee_rate);
                     }
                 }
+
+                if (is_cpfp) {
+                    // Report the effective fee rate of the parent and child together,
+                    // which is what miners consider when selecting the pair
+                    const uint32_t vsize = result.at("transaction_vsize");
+                    const uint32_t prev_vsize = result.at("previous_transaction").at("transaction_vsize");
+                    result["package_fee_rate"] = (fee + old_fee).value() * 1000 / (vsize + prev_vsize);
+                }
             };
 
+            if (is_issuance && num_addressees) {
+                for (auto& addressee : *addressees_p) {
+                    if (json_get_value(addressee, "is_issuance", false)) {
+                        add_tx_addressee(session, net_params, result, tx, addressee);
+                    }
+                }
+            }
+
             if (is_liquid) {
                 std::for_each(std::begin(asset_tags), std::end(asset_tags), [&](const auto& asset_tag) {
                     if (asset_tag != "btc") {
@@ -790,10 +1201,28 @@ namespace sdk {
             // do fee output + L-BTC outputs
             create_tx_outputs("btc");
 
+            if (is_consolidation && json_get_value(result, "error").empty()) {
+                add_consolidation_info(session, result);
+            }
+
             if (used_utxos.size() > 1u && json_get_value(result, "randomize_inputs", true)) {
                 randomise_inputs(tx, used_utxos);
             }
 
+            if (!result.at("addressees_read_only") && json_get_value(result, "error").empty()) {
+                flag_known_addressees(session, result, subaccount);
+            }
+
+            if (is_liquid && json_get_value(result, "error").empty()) {
+                // Confidential inputs can only be balanced by blinding at least one output
+                const auto& outputs = result.at("transaction_outputs");
+                if (std::none_of(outputs.begin(), outputs.end(), [](const auto& output) {
+                        return !output.at("is_fee") && !json_get_value(output, "is_unconfidential", false);
+                    })) {
+                    set_tx_error(result, "At least one output must be confidential");
+                }
+            }
+
             if (is_liquid && json_get_value(result, "error").empty()) {
                 result = blind_ga_transaction(session, result);
             }
@@ -948,7 +1377,7 @@ namespace sdk {
 
         const auto tx = tx_from_hex(details.at("transaction"), WALLY_TX_FLAG_USE_WITNESS | WALLY_TX_FLAG_USE_ELEMENTS);
 
-        const auto num_inputs = details.at("used_utxos").size();
+        auto num_inputs = details.at("used_utxos").size();
 
         std::vector<unsigned char> input_assets;
         std::vector<unsigned char> input_abfs;
@@ -965,40 +1394,107 @@ namespace sdk {
             const auto vbf = h2b(utxo["vbf"]);
             input_vbfs.insert(input_vbfs.end(), std::begin(vbf), std::end(vbf));
             input_values.emplace_back(utxo["satoshi"]);
+
+            for (const auto& issued : get_issued_amounts(utxo)) {
+                const auto issued_asset_id = h2b_rev(issued.first);
+                const abf_t issued_abf{ { 0 } };
+                const vbf_t issued_vbf{ { 0 } };
+                const auto issued_generator = asset_generator_from_bytes(issued_asset_id, issued_abf);
+                input_assets.insert(input_assets.end(), std::begin(issued_asset_id), std::end(issued_asset_id));
+                input_ags.insert(input_ags.end(), std::begin(issued_generator), std::end(issued_generator));
+                input_abfs.insert(input_abfs.end(), std::begin(issued_abf), std::end(issued_abf));
+                input_vbfs.insert(input_vbfs.end(), std::begin(issued_vbf), std::end(issued_vbf));
+                input_values.emplace_back(issued.second);
+                ++num_inputs;
+            }
         }
 
+        // Explicit outputs have zero blinding factors, so like the fee they don't
+        // affect the final value blinding factor
         size_t num_outputs{ 0 };
-        const auto transaction_outputs = details.at("transaction_outputs");
+        auto transaction_outputs = details.at("transaction_outputs");
         for (const auto& output : transaction_outputs) {
-            if (output.at("is_fee")) {
+            if (output.at("is_fee") || json_get_value(output, "is_unconfidential", false)) {
                 continue;
             }
             input_values.emplace_back(output["satoshi"]);
             ++num_outputs;
         }
+        if (num_outputs == 0) {
+            // Nothing to blind yet, e.g. while estimating the fee before change is added
+            return details;
+        }
+
+        // Blinders are random unless the caller wants them derived from the inputs being
+        // spent. Each output's blinders also commit to its script, asset and value, so that
+        // a replacement or rebuilt tx spending the same inputs never reuses them (and so the
+        // rangeproof nonce) for a different value
+        const bool deterministic = json_get_value(details, "deterministic_blinding", false);
+        std::vector<std::vector<unsigned char>> outputs_data;
+        if (deterministic) {
+            auto&& push_le = [](std::vector<unsigned char>& data, uint64_t v, size_t len) {
+                for (size_t j = 0; j < len; ++j) {
+                    data.push_back(static_cast<unsigned char>(v >> (j * 8)));
+                }
+            };
+            std::vector<unsigned char> prevouts;
+            for (size_t j = 0; j < tx->num_inputs; ++j) {
+                const auto& input = tx->inputs[j];
+                prevouts.insert(prevouts.end(), std::begin(input.txhash), std::end(input.txhash));
+                push_le(prevouts, input.index, sizeof(input.index));
+            }
+            const auto hash_prevouts = sha256d(prevouts);
+
+            uint32_t index = 0;
+            for (const auto& output : transaction_outputs) {
+                if (output.at("is_fee")) {
+                    break; // The fee is always the last output
+                }
+                if (!json_get_value(output, "is_unconfidential", false)) {
+                    const auto& tx_output = tx->outputs[index];
+                    const auto asset_id = h2b_rev(output.at("asset_id"));
+                    std::vector<unsigned char> data(hash_prevouts.begin(), hash_prevouts.end());
+                    push_le(data, index, sizeof(index));
+                    data.insert(data.end(), tx_output.script, tx_output.script + tx_output.script_len);
+                    data.insert(data.end(), asset_id.begin(), asset_id.end());
+                    push_le(data, output.at("satoshi").get<uint64_t>(), sizeof(uint64_t));
+                    const auto output_hash = sha256(data);
+                    outputs_data.emplace_back(output_hash.begin(), output_hash.end());
+                }
+                ++index;
+            }
+        }
+        auto&& get_blinder = [&](size_t blinded_index, const std::string& type) -> std::array<unsigned char, 32> {
+            if (deterministic) {
+                return session.get_deterministic_blinder(outputs_data.at(blinded_index), type);
+            }
+            return get_random_bytes<32>();
+        };
 
         std::vector<abf_t> output_abfs;
         output_abfs.reserve(num_outputs);
         for (size_t i = 0; i < num_outputs; ++i) {
-            output_abfs.emplace_back(get_random_bytes<32>());
+            output_abfs.emplace_back(get_blinder(i, "abf"));
         }
 
         std::vector<vbf_t> output_vbfs;
         output_vbfs.reserve(num_outputs - 1);
         for (size_t i = 0; i < num_outputs - 1; ++i) {
-            output_vbfs.emplace_back(get_random_bytes<32>());
+            output_vbfs.emplace_back(get_blinder(i, "vbf"));
         }
 
         output_vbfs.emplace_back(
             generate_final_vbf(input_abfs, input_vbfs, input_values, output_abfs, output_vbfs, num_inputs));
 
         size_t i = 0;
+        uint32_t output_index = 0;
         const std::string subaccount_type = details["subaccount_type"];
         const bool authorized_assets = subaccount_type == "2of2_no_recovery";
 
         std::vector<std::string> blinding_nonces;
+        nlohmann::json blinded_outputs = nlohmann::json::array();
 
-        for (const auto& output : transaction_outputs) {
+        for (auto& output : transaction_outputs) {
             // IMPORTANT: we assume the fee is always the last output
             if (output.at("is_fee")) {
                 if (authorized_assets) {
@@ -1007,6 +1503,22 @@ namespace sdk {
                 break;
             }
 
+            if (json_get_value(output, "is_unconfidential", false)) {
+                // Left explicit as requested by the caller
+                if (authorized_assets) {
+                    blinding_nonces.emplace_back(std::string{});
+                }
+                ++output_index;
+                continue;
+            }
+
+            if (deterministic) {
+                const auto eph_keypair_sec = get_blinder(i, "eph");
+                output["eph_keypair_sec"] = b2h(eph_keypair_sec);
+                output["eph_keypair_pub"] = b2h(ec_public_key_from_private_key(eph_keypair_sec));
+                output["surjectionproof_seed"] = b2h(get_blinder(i, "seed"));
+            }
+
             const auto asset_id = h2b_rev(output.at("asset_id"));
             const auto pub_key = h2b(output.at("public_key"));
             const uint64_t value = output.at("satoshi");
@@ -1014,7 +1526,11 @@ namespace sdk {
             const auto generator = asset_generator_from_bytes(asset_id, output_abfs[i]);
             const auto value_commitment = asset_value_commitment(value, output_vbfs[i], generator);
 
-            blind_output(session, details, tx, i, output, generator, value_commitment, output_abfs[i], output_vbfs[i]);
+            blind_output(session, details, tx, output_index, output, generator, value_commitment, output_abfs[i],
+                output_vbfs[i]);
+            blinded_outputs.push_back({ { "index", output_index }, { "asset_id", output.at("asset_id") },
+                { "satoshi", value }, { "abf", b2h(output_abfs[i]) }, { "vbf", b2h(output_vbfs[i]) },
+                { "asset_commitment", b2h(generator) }, { "value_commitment", b2h(value_commitment) } });
 
             if (authorized_assets) {
                 const auto eph_keypair_sec = h2b(output.at("eph_keypair_sec"));
@@ -1023,10 +1539,15 @@ namespace sdk {
             }
 
             ++i;
+            ++output_index;
         }
 
         nlohmann::json result(details);
         result["blinded"] = true;
+        // Keep the explicit tx and what was hidden in each output, for review before sending
+        result["unblinded_transaction"] = details.at("transaction");
+        result["blinded_outputs"] = blinded_outputs;
+        result["transaction_outputs"] = transaction_outputs;
         if (authorized_assets) {
             result["blinding_nonces"] = blinding_nonces;
         }
@@ -1059,6 +1580,16 @@ namespace sdk {
             const auto generator = asset_generator_from_bytes(asset_id, abf);
             input_ags.insert(input_ags.end(), std::begin(generator), std::end(generator));
             input_abfs.insert(input_abfs.end(), std::begin(abf), std::end(abf));
+
+            // Issued assets can be surjected to their explicit issuance
+            for (const auto& issued : get_issued_amounts(utxo)) {
+                const auto issued_asset_id = h2b_rev(issued.first);
+                const abf_t issued_abf{ { 0 } };
+                const auto issued_generator = asset_generator_from_bytes(issued_asset_id, issued_abf);
+                input_assets.insert(input_assets.end(), std::begin(issued_asset_id), std::end(issued_asset_id));
+                input_ags.insert(input_ags.end(), std::begin(issued_generator), std::end(issued_generator));
+                input_abfs.insert(input_abfs.end(), std::begin(issued_abf), std::end(issued_abf));
+            }
         }
 
         const auto asset_id = h2b_rev(output.at("asset_id"));
@@ -1072,8 +1603,10 @@ namespace sdk {
         const auto rangeproof = asset_rangeproof(value, pub_key, eph_keypair_sec, asset_id, abf, vbf, value_commitment,
             script, generator, 1, std::min(std::max(net_params.ct_exponent(), -1), 18), net_params.ct_bits());
 
-        const auto surjectionproof = asset_surjectionproof(
-            asset_id, abf, generator, get_random_bytes<32>(), input_assets, input_abfs, input_ags);
+        const std::string seed_hex = json_get_value(output, "surjectionproof_seed");
+        const auto seed = seed_hex.empty() ? get_random_bytes<32>() : h2b<32>(seed_hex);
+        const auto surjectionproof
+            = asset_surjectionproof(asset_id, abf, generator, seed, input_assets, input_abfs, input_ags);
 
         tx_elements_output_commitment_set(
             tx, index, generator, value_commitment, eph_keypair_pub, surjectionproof, rangeproof);
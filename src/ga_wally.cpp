@@ -458,6 +458,22 @@ namespace sdk {
         return ret;
     }
 
+    bool ec_sig_verify(byte_span_t public_key, byte_span_t message_hash, byte_span_t sig)
+    {
+        const uint32_t flags = EC_FLAG_ECDSA;
+        return wally_ec_sig_verify(public_key.data(), public_key.size(), message_hash.data(), message_hash.size(),
+                   flags, sig.data(), sig.size())
+            == WALLY_OK;
+    }
+
+    std::vector<unsigned char> ec_sig_to_public_key(byte_span_t message_hash, byte_span_t recoverable_sig)
+    {
+        std::vector<unsigned char> ret(EC_PUBLIC_KEY_LEN);
+        GDK_VERIFY(wally_ec_sig_to_public_key(message_hash.data(), message_hash.size(), recoverable_sig.data(),
+            recoverable_sig.size(), ret.data(), ret.size()));
+        return ret;
+    }
+
     std::vector<unsigned char> ec_public_key_from_private_key(byte_span_t private_key)
     {
         std::vector<unsigned char> ret(EC_PUBLIC_KEY_LEN);
@@ -681,6 +697,32 @@ namespace sdk {
         return priv_key;
     }
 
+    std::array<unsigned char, SHA256_LEN> tx_elements_issuance_generate_entropy(
+        byte_span_t txhash, uint32_t index, byte_span_t contract_hash)
+    {
+        std::array<unsigned char, SHA256_LEN> entropy;
+        GDK_VERIFY(wally_tx_elements_issuance_generate_entropy(txhash.data(), txhash.size(), index,
+            contract_hash.data(), contract_hash.size(), entropy.data(), entropy.size()));
+        return entropy;
+    }
+
+    std::array<unsigned char, SHA256_LEN> tx_elements_issuance_calculate_asset(byte_span_t entropy)
+    {
+        std::array<unsigned char, SHA256_LEN> asset;
+        GDK_VERIFY(
+            wally_tx_elements_issuance_calculate_asset(entropy.data(), entropy.size(), asset.data(), asset.size()));
+        return asset;
+    }
+
+    std::array<unsigned char, SHA256_LEN> tx_elements_issuance_calculate_reissuance_token(
+        byte_span_t entropy, uint32_t flags)
+    {
+        std::array<unsigned char, SHA256_LEN> token;
+        GDK_VERIFY(wally_tx_elements_issuance_calculate_reissuance_token(
+            entropy.data(), entropy.size(), flags, token.data(), token.size()));
+        return token;
+    }
+
     //
     // Transactions
     //
@@ -777,6 +819,15 @@ namespace sdk {
         }
     }
 
+    void tx_elements_input_issuance_set(const wally_tx_ptr& tx, size_t index, byte_span_t nonce,
+        byte_span_t entropy, byte_span_t issuance_amount, byte_span_t inflation_keys)
+    {
+        GDK_RUNTIME_ASSERT(index < tx->num_inputs);
+        GDK_VERIFY(wally_tx_elements_input_issuance_set(tx->inputs + index, nonce.data(), nonce.size(),
+            entropy.data(), entropy.size(), issuance_amount.data(), issuance_amount.size(),
+            inflation_keys.empty() ? nullptr : inflation_keys.data(), inflation_keys.size(), nullptr, 0, nullptr, 0));
+    }
+
     size_t tx_get_vsize(const wally_tx_ptr& tx)
     {
         size_t written;
@@ -201,6 +201,12 @@ namespace sdk {
     std::vector<unsigned char> ec_sig_to_der(byte_span_t sig, bool sighash = false);
     ecdsa_sig_t ec_sig_from_der(byte_span_t der, bool sighash = false);
 
+    bool ec_sig_verify(byte_span_t public_key, byte_span_t message_hash, byte_span_t sig);
+
+    // Recover the compressed public key from a recoverable signature, i.e. a
+    // header byte giving the recovery id followed by a compact signature
+    std::vector<unsigned char> ec_sig_to_public_key(byte_span_t message_hash, byte_span_t recoverable_sig);
+
     std::vector<unsigned char> ec_public_key_from_private_key(byte_span_t private_key);
 
     std::vector<unsigned char> ec_public_key_decompress(byte_span_t public_key);
@@ -254,6 +260,14 @@ namespace sdk {
 
     priv_key_t asset_blinding_key_to_ec_private_key(byte_span_t blinding_key, byte_span_t script);
 
+    std::array<unsigned char, SHA256_LEN> tx_elements_issuance_generate_entropy(
+        byte_span_t txhash, uint32_t index, byte_span_t contract_hash);
+
+    std::array<unsigned char, SHA256_LEN> tx_elements_issuance_calculate_asset(byte_span_t entropy);
+
+    std::array<unsigned char, SHA256_LEN> tx_elements_issuance_calculate_reissuance_token(
+        byte_span_t entropy, uint32_t flags = 0);
+
     //
     // Transactions
     //
@@ -287,6 +301,9 @@ namespace sdk {
     void tx_add_raw_input(const wally_tx_ptr& tx, byte_span_t txhash, uint32_t index, uint32_t sequence,
         byte_span_t script, const wally_tx_witness_stack_ptr& witness = {});
 
+    void tx_elements_input_issuance_set(const wally_tx_ptr& tx, size_t index, byte_span_t nonce,
+        byte_span_t entropy, byte_span_t issuance_amount, byte_span_t inflation_keys);
+
     GA_USE_RESULT size_t tx_get_vsize(const wally_tx_ptr& tx);
 
     GA_USE_RESULT size_t tx_get_weight(const wally_tx_ptr& tx);
@@ -1,4 +1,6 @@
+#include <array>
 #include <mutex>
+#include <tuple>
 
 #include "assertion.hpp"
 #include "containers.hpp"
@@ -192,6 +194,28 @@ namespace sdk {
         }
     }
 
+    nlohmann::json network_parameters::dust_thresholds() const
+    {
+        // Serialized size of an output of each script type plus the size of
+        // the input that spends it, as used by bitcoind to compute dust
+        static const std::array<std::tuple<const char*, uint32_t, uint32_t>, 4> script_sizes = { {
+            { "p2pkh", 34, 148 },
+            { "p2sh", 32, 148 },
+            { "p2wpkh", 31, 67 },
+            { "p2wsh", 43, 67 },
+        } };
+        // Explicit Elements outputs also carry an asset tag, a 9 byte value and an empty nonce
+        const uint32_t extra_output_size = liquid() ? 35 : 0;
+        const uint64_t rate = dust_relay_fee_rate();
+
+        nlohmann::json ret;
+        for (const auto& s : script_sizes) {
+            const uint64_t size = std::get<1>(s) + extra_output_size + std::get<2>(s);
+            ret[std::get<0>(s)] = size * rate / 1000;
+        }
+        return ret;
+    }
+
     nlohmann::json network_parameters::get_all()
     {
         // We manually order mainnet/liquid/testnet first for nice wallet/UX display ordering
@@ -60,6 +60,15 @@ namespace sdk {
             return use_tor ? asset_registry_onion_url() : asset_registry_url();
         }
         std::vector<uint32_t> csv_buckets() const { return m_details.at("csv_buckets"); }
+        // Policy values, overridable for custom chains. Fee rates are in satoshi per 1000 bytes
+        uint32_t min_fee_rate() const { return m_details.value("min_fee_rate", 1000u); }
+        uint32_t dust_relay_fee_rate() const { return m_details.value("dust_relay_fee_rate", 3000u); }
+        uint32_t dust_threshold() const { return m_details.value("dust_threshold", 0u); }
+        std::string policy_asset_ticker() const
+        {
+            return m_details.value("policy_asset_ticker", std::string(main_net() ? "L-BTC" : "tL-BTC"));
+        }
+        nlohmann::json dust_thresholds() const;
 
     private:
         nlohmann::json m_details;
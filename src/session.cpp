@@ -100,6 +100,8 @@ namespace sdk {
             std::rethrow_exception(std::current_exception());
         } catch (const reconnect_error& e) {
             std::rethrow_exception(std::current_exception());
+        } catch (const cancelled_error& e) {
+            std::rethrow_exception(std::current_exception());
         } catch (const timeout_error& e) {
             reconnect();
             throw reconnect_error();
@@ -186,6 +188,14 @@ namespace sdk {
         });
     }
 
+    void session::cancel_pending_requests()
+    {
+        exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            p->cancel_pending_requests();
+        });
+    }
+
     bool session::check_proxy_connectivity(const nlohmann::json& params)
     {
         boost::asio::io_context io;
@@ -240,6 +250,22 @@ namespace sdk {
         });
     }
 
+    nlohmann::json session::lookup_asset(const nlohmann::json& params)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->lookup_asset(params);
+        });
+    }
+
+    void session::import_blinders(const nlohmann::json& details)
+    {
+        exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            p->import_blinders(details);
+        });
+    }
+
     nlohmann::json session::validate_asset_domain_name(const nlohmann::json& params)
     {
         return exception_wrapper([&] {
@@ -290,6 +316,14 @@ namespace sdk {
         });
     }
 
+    bool session::resume_from_snapshot(const std::string& master_xpub)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->resume_from_snapshot(master_xpub);
+        });
+    }
+
     void session::login(const std::string& mnemonic, const std::string& password)
     {
         return exception_wrapper([&] {
@@ -298,6 +332,22 @@ namespace sdk {
         });
     }
 
+    void session::set_cache_encryption_key(const nlohmann::json& details)
+    {
+        exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            p->set_cache_encryption_key(details);
+        });
+    }
+
+    void session::change_cache_password(const nlohmann::json& details)
+    {
+        exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            p->change_cache_password(details);
+        });
+    }
+
     void session::login_with_pin(const std::string& pin, const nlohmann::json& pin_data)
     {
         return exception_wrapper([&] {
@@ -387,6 +437,14 @@ namespace sdk {
         });
     }
 
+    void session::set_subaccount_hidden(uint32_t subaccount, bool hidden)
+    {
+        exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            p->set_subaccount_hidden(subaccount, hidden);
+        });
+    }
+
     nlohmann::json session::get_settings()
     {
         return exception_wrapper([&] {
@@ -475,6 +533,62 @@ namespace sdk {
         });
     }
 
+    nlohmann::json session::get_balance_detailed(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_balance_detailed(details);
+        });
+    }
+
+    nlohmann::json session::get_balance_history(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_balance_history(details);
+        });
+    }
+
+    nlohmann::json session::get_spending_summary(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_spending_summary(details);
+        });
+    }
+
+    nlohmann::json session::get_generated_addresses(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_generated_addresses(details);
+        });
+    }
+
+    nlohmann::json session::rescan_transactions(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->rescan_transactions(details);
+        });
+    }
+
+    nlohmann::json session::get_sync_status()
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_sync_status();
+        });
+    }
+
+    nlohmann::json session::export_unblinded_outputs(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->export_unblinded_outputs(details);
+        });
+    }
+
     nlohmann::json session::get_available_currencies()
     {
         return exception_wrapper([&] {
@@ -687,6 +801,38 @@ namespace sdk {
         });
     }
 
+    void session::freeze_utxo(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->freeze_utxo(details);
+        });
+    }
+
+    void session::unfreeze_utxo(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->unfreeze_utxo(details);
+        });
+    }
+
+    nlohmann::json session::get_utxo_policy()
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_utxo_policy();
+        });
+    }
+
+    void session::set_utxo_policy(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->set_utxo_policy(details);
+        });
+    }
+
     nlohmann::json session::create_transaction(const nlohmann::json& details)
     {
         return exception_wrapper([&] {
@@ -695,6 +841,14 @@ namespace sdk {
         });
     }
 
+    nlohmann::json session::can_sign_transaction(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->can_sign_transaction(details);
+        });
+    }
+
     nlohmann::json session::sign_transaction(const nlohmann::json& details)
     {
         return exception_wrapper([&] {
@@ -795,6 +949,22 @@ namespace sdk {
         });
     }
 
+    nlohmann::json session::get_transaction_blinders(const std::string& txhash_hex)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_transaction_blinders(txhash_hex);
+        });
+    }
+
+    nlohmann::json session::decode_transaction(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->decode_transaction(details);
+        });
+    }
+
     std::string session::get_system_message()
     {
         return exception_wrapper([&] {
@@ -803,6 +973,94 @@ namespace sdk {
         });
     }
 
+    nlohmann::json session::get_network_details()
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_network_details();
+        });
+    }
+
+    nlohmann::json session::estimate_transaction_fee(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->estimate_transaction_fee(details);
+        });
+    }
+
+    nlohmann::json session::get_fee_bump_options(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_fee_bump_options(details);
+        });
+    }
+
+    nlohmann::json session::get_event_journal()
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_event_journal();
+        });
+    }
+
+    nlohmann::json session::get_cache_stats()
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_cache_stats();
+        });
+    }
+
+    nlohmann::json session::get_denylist()
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_denylist();
+        });
+    }
+
+    void session::set_denylist(const nlohmann::json& denylist)
+    {
+        exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            p->set_denylist(denylist);
+        });
+    }
+
+    nlohmann::json session::get_address_labels(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_address_labels(details);
+        });
+    }
+
+    void session::set_address_label(const nlohmann::json& details)
+    {
+        exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            p->set_address_label(details);
+        });
+    }
+
+    nlohmann::json session::get_transaction_templates()
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->get_transaction_templates();
+        });
+    }
+
+    void session::set_transaction_template(const nlohmann::json& details)
+    {
+        exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            p->set_transaction_template(details);
+        });
+    }
+
     nlohmann::json session::get_fee_estimates()
     {
         return exception_wrapper([&] {
@@ -841,6 +1099,28 @@ namespace sdk {
         });
     }
 
+    nlohmann::json session::get_message_signing_details(const nlohmann::json& details)
+    {
+        auto p = get_nonnull_impl();
+        return p->get_message_signing_details(details); // Note no exception wrapper
+    }
+
+    nlohmann::json session::sign_message(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->sign_message(details);
+        });
+    }
+
+    bool session::verify_message(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->verify_message(details);
+        });
+    }
+
     nlohmann::json session::convert_amount(const nlohmann::json& amount_json)
     {
         return exception_wrapper([&] {
@@ -849,6 +1129,30 @@ namespace sdk {
         });
     }
 
+    nlohmann::json session::parse_amount(const nlohmann::json& amount_details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->parse_amount(amount_details);
+        });
+    }
+
+    nlohmann::json session::parse_uri(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->parse_uri(details);
+        });
+    }
+
+    nlohmann::json session::parse_address(const nlohmann::json& details)
+    {
+        return exception_wrapper([&] {
+            auto p = get_nonnull_impl();
+            return p->parse_address(details);
+        });
+    }
+
     amount session::get_min_fee_rate() const
     {
         auto p = get_nonnull_impl();
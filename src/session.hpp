@@ -43,11 +43,14 @@ namespace sdk {
         void connect(const nlohmann::json& net_params);
         void disconnect();
         void reconnect_hint(const nlohmann::json& hint);
+        void cancel_pending_requests();
         std::string get_tor_socks5();
 
         static bool check_proxy_connectivity(const nlohmann::json& params);
         nlohmann::json http_get(const nlohmann::json& params);
         nlohmann::json refresh_assets(const nlohmann::json& params);
+        nlohmann::json lookup_asset(const nlohmann::json& params);
+        void import_blinders(const nlohmann::json& details);
         nlohmann::json validate_asset_domain_name(const nlohmann::json& params);
 
         void register_user(const std::string& mnemonic, bool supports_csv);
@@ -58,7 +61,10 @@ namespace sdk {
         void authenticate(const std::string& sig_der_hex, const std::string& path_hex, const std::string& device_id,
             const nlohmann::json& hw_device);
         void register_subaccount_xpubs(const std::vector<std::string>& bip32_xpubs);
+        bool resume_from_snapshot(const std::string& master_xpub);
         void login(const std::string& mnemonic, const std::string& password);
+        void set_cache_encryption_key(const nlohmann::json& details);
+        void change_cache_password(const nlohmann::json& details);
         void login_with_pin(const std::string& pin, const nlohmann::json& pin_data);
         void login_watch_only(const std::string& username, const std::string& password);
         bool set_watch_only(const std::string& username, const std::string& password);
@@ -86,8 +92,16 @@ namespace sdk {
         nlohmann::json get_subaccount(uint32_t subaccount);
 
         void rename_subaccount(uint32_t subaccount, const std::string& new_name);
+        void set_subaccount_hidden(uint32_t subaccount, bool hidden);
 
         nlohmann::json get_balance(const nlohmann::json& details);
+        nlohmann::json get_balance_detailed(const nlohmann::json& details);
+        nlohmann::json get_balance_history(const nlohmann::json& details);
+        nlohmann::json get_spending_summary(const nlohmann::json& details);
+        nlohmann::json get_generated_addresses(const nlohmann::json& details);
+        nlohmann::json rescan_transactions(const nlohmann::json& details);
+        nlohmann::json get_sync_status();
+        nlohmann::json export_unblinded_outputs(const nlohmann::json& details);
 
         nlohmann::json get_available_currencies();
 
@@ -125,13 +139,20 @@ namespace sdk {
         nlohmann::json get_unspent_outputs(const nlohmann::json& details);
         nlohmann::json get_unspent_outputs_for_private_key(
             const std::string& private_key, const std::string& password, uint32_t unused);
+        void freeze_utxo(const nlohmann::json& details);
+        void unfreeze_utxo(const nlohmann::json& details);
+        nlohmann::json get_utxo_policy();
+        void set_utxo_policy(const nlohmann::json& details);
         nlohmann::json get_transaction_details(const std::string& txhash_hex);
+        nlohmann::json get_transaction_blinders(const std::string& txhash_hex);
+        nlohmann::json decode_transaction(const nlohmann::json& details);
 
         bool has_blinding_nonce(const std::string& pubkey, const std::string& script);
         void set_blinding_nonce(const std::string& pubkey, const std::string& script, const std::string& nonce);
 
         nlohmann::json create_transaction(const nlohmann::json& details);
         nlohmann::json sign_transaction(const nlohmann::json& details);
+        nlohmann::json can_sign_transaction(const nlohmann::json& details);
         nlohmann::json send_transaction(const nlohmann::json& details, const nlohmann::json& twofactor_data);
         std::string broadcast_transaction(const std::string& tx_hex);
 
@@ -150,6 +171,8 @@ namespace sdk {
         void upload_confidential_addresses(uint32_t subaccount, std::vector<std::string> confidential_addresses);
 
         nlohmann::json get_fee_estimates();
+        nlohmann::json estimate_transaction_fee(const nlohmann::json& details);
+        nlohmann::json get_fee_bump_options(const nlohmann::json& details);
 
         std::string get_mnemonic_passphrase(const std::string& password);
 
@@ -158,13 +181,29 @@ namespace sdk {
         void ack_system_message(const std::string& system_message);
         void ack_system_message(const std::string& message_hash_hex, const std::string& sig_der_hex);
 
+        nlohmann::json get_message_signing_details(const nlohmann::json& details);
+        nlohmann::json sign_message(const nlohmann::json& details);
+        bool verify_message(const nlohmann::json& details);
+
         nlohmann::json convert_amount(const nlohmann::json& amount_json);
+        nlohmann::json parse_amount(const nlohmann::json& amount_details);
+        nlohmann::json parse_uri(const nlohmann::json& details);
+        nlohmann::json parse_address(const nlohmann::json& details);
 
         amount get_min_fee_rate() const;
         amount get_default_fee_rate() const;
         bool have_subaccounts() const;
         uint32_t get_block_height() const;
         amount get_dust_threshold() const;
+        nlohmann::json get_network_details();
+        nlohmann::json get_event_journal();
+        nlohmann::json get_cache_stats();
+        nlohmann::json get_denylist();
+        void set_denylist(const nlohmann::json& denylist);
+        nlohmann::json get_address_labels(const nlohmann::json& details);
+        void set_address_label(const nlohmann::json& details);
+        nlohmann::json get_transaction_templates();
+        void set_transaction_template(const nlohmann::json& details);
         nlohmann::json get_spending_limits() const;
         bool is_spending_limits_decrease(const nlohmann::json& limit_details);
 
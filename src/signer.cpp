@@ -51,6 +51,12 @@ namespace sdk {
         return ec_public_key_from_private_key(get_blinding_key_from_script(script));
     }
 
+    priv_key_t signer::get_deterministic_blinder(__attribute__((unused)) byte_span_t data)
+    {
+        GDK_RUNTIME_ASSERT_MSG(false, "Deterministic blinding requires a software wallet");
+        __builtin_unreachable();
+    }
+
     //
     // Watch-only signer
     //
@@ -186,6 +192,19 @@ namespace sdk {
         return asset_blinding_key_to_ec_private_key(*m_master_blinding_key, script);
     }
 
+    priv_key_t software_signer::get_deterministic_blinder(byte_span_t data)
+    {
+        // Keyed by the whole master blinding key and tagged, so that blinders can never
+        // coincide with SLIP-77 blinding keys, which use only its second half
+        GDK_RUNTIME_ASSERT(m_master_blinding_key.has_value());
+        static const std::string tag("GDK deterministic blinder");
+        std::vector<unsigned char> tagged(tag.begin(), tag.end());
+        tagged.insert(tagged.end(), data.begin(), data.end());
+        const auto blinder = hmac_sha256(*m_master_blinding_key, tagged);
+        GDK_RUNTIME_ASSERT(ec_private_key_verify(blinder));
+        return blinder;
+    }
+
     //
     // Hardware signer
     //
@@ -63,6 +63,9 @@ namespace sdk {
 
         virtual std::vector<unsigned char> get_public_key_from_blinding_key(byte_span_t script);
 
+        // Derive a blinding factor or ephemeral key for deterministic blinding from 'data'
+        virtual priv_key_t get_deterministic_blinder(byte_span_t data);
+
     protected:
         const network_parameters& m_net_params;
     };
@@ -117,6 +120,7 @@ namespace sdk {
 
         ecdsa_sig_t sign_hash(gsl::span<const uint32_t> path, gsl::span<const unsigned char> hash) override;
         priv_key_t get_blinding_key_from_script(byte_span_t script) override;
+        priv_key_t get_deterministic_blinder(byte_span_t data) override;
 
     private:
         wally_ext_key_ptr m_master_key;
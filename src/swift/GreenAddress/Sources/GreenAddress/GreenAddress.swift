@@ -10,6 +10,7 @@ public enum GaError: Error {
     case SessionLost
     case TimeoutError
     case NotAuthorizedError
+    case CancelledError
 }
 
 fileprivate func errorWrapper(_ r: Int32) throws {
@@ -23,6 +24,8 @@ fileprivate func errorWrapper(_ r: Int32) throws {
                 throw GaError.TimeoutError
             case GA_NOT_AUTHORIZED:
                 throw GaError.NotAuthorizedError
+            case GA_CANCELLED:
+                throw GaError.CancelledError
             default:
                 throw GaError.GenericError
         }
@@ -198,6 +201,10 @@ public class Session {
         try callWrapper(fun: GA_reconnect_hint(session, hintJson))
     }
 
+    public func cancelPendingRequests() throws {
+        try callWrapper(fun: GA_cancel_pending_requests(session))
+    }
+
     public func getTorSocks5() throws -> String {
         var buff: UnsafeMutablePointer<Int8>? = nil
         try callWrapper(fun: GA_get_tor_socks5(session, &buff))
@@ -227,6 +234,22 @@ public class Session {
         return TwoFactorCall(optr: optr!);
     }
 
+    public func setCacheEncryptionKey(details: [String: Any]) throws -> Void {
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        defer {
+            GA_destroy_json(details_json)
+        }
+        try callWrapper(fun: GA_set_cache_encryption_key(session, details_json))
+    }
+
+    public func changeCachePassword(details: [String: Any]) throws -> Void {
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        defer {
+            GA_destroy_json(details_json)
+        }
+        try callWrapper(fun: GA_change_cache_password(session, details_json))
+    }
+
     public func loginWithPin(pin: String, pin_data:String) throws {
         var result: OpaquePointer? = nil
         defer {
@@ -285,6 +308,10 @@ public class Session {
         try callWrapper(fun: GA_rename_subaccount(session, subaccount, newName));
     }
 
+    public func setSubaccountHidden(subaccount: UInt32, hidden: Bool) throws -> Void {
+        try callWrapper(fun: GA_set_subaccount_hidden(session, subaccount, UInt32(hidden ? 1 : 0)));
+    }
+
     public func getTransactions(details: [String: Any]) throws -> TwoFactorCall {
         var optr: OpaquePointer? = nil
         var details_json: OpaquePointer = try convertDictToJSON(dict: details)
@@ -311,6 +338,36 @@ public class Session {
         return try convertOpaqueJsonToDict(o: result!)
     }
 
+    public func freezeUtxo(details: [String: Any]) throws -> Void {
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        defer {
+            GA_destroy_json(details_json)
+        }
+        try callWrapper(fun: GA_freeze_utxo(session, details_json))
+    }
+
+    public func unfreezeUtxo(details: [String: Any]) throws -> Void {
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        defer {
+            GA_destroy_json(details_json)
+        }
+        try callWrapper(fun: GA_unfreeze_utxo(session, details_json))
+    }
+
+    public func getUtxoPolicy() throws -> [String: Any]? {
+        var result: OpaquePointer? = nil
+        try callWrapper(fun: GA_get_utxo_policy(session, &result))
+        return try convertOpaqueJsonToDict(o: result!)
+    }
+
+    public func setUtxoPolicy(details: [String: Any]) throws -> Void {
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        defer {
+            GA_destroy_json(details_json)
+        }
+        try callWrapper(fun: GA_set_utxo_policy(session, details_json))
+    }
+
     public func getReceiveAddress(details: [String: Any]) throws -> TwoFactorCall {
         var optr: OpaquePointer? = nil
         var details_json: OpaquePointer = try convertDictToJSON(dict: details)
@@ -321,6 +378,16 @@ public class Session {
         return TwoFactorCall(optr: optr!)
     }
 
+    public func getReceivePayload(details: [String: Any]) throws -> TwoFactorCall {
+        var optr: OpaquePointer? = nil
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        try callWrapper(fun: GA_get_receive_payload(session, details_json, &optr))
+        defer {
+            GA_destroy_json(details_json)
+        }
+        return TwoFactorCall(optr: optr!)
+    }
+
     public func getBalance(details: [String: Any]) throws -> TwoFactorCall {
         var optr: OpaquePointer? = nil
         var details_json: OpaquePointer = try convertDictToJSON(dict: details)
@@ -331,6 +398,72 @@ public class Session {
         return TwoFactorCall(optr: optr!)
     }
 
+    public func getBalanceDetailed(details: [String: Any]) throws -> TwoFactorCall {
+        var optr: OpaquePointer? = nil
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        try callWrapper(fun: GA_get_balance_detailed(session, details_json, &optr))
+        defer {
+            GA_destroy_json(details_json)
+        }
+        return TwoFactorCall(optr: optr!)
+    }
+
+    public func getBalanceHistory(details: [String: Any]) throws -> TwoFactorCall {
+        var optr: OpaquePointer? = nil
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        try callWrapper(fun: GA_get_balance_history(session, details_json, &optr))
+        defer {
+            GA_destroy_json(details_json)
+        }
+        return TwoFactorCall(optr: optr!)
+    }
+
+    public func getSpendingSummary(details: [String: Any]) throws -> TwoFactorCall {
+        var optr: OpaquePointer? = nil
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        try callWrapper(fun: GA_get_spending_summary(session, details_json, &optr))
+        defer {
+            GA_destroy_json(details_json)
+        }
+        return TwoFactorCall(optr: optr!)
+    }
+
+    public func getGeneratedAddresses(details: [String: Any]) throws -> TwoFactorCall {
+        var optr: OpaquePointer? = nil
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        try callWrapper(fun: GA_get_generated_addresses(session, details_json, &optr))
+        defer {
+            GA_destroy_json(details_json)
+        }
+        return TwoFactorCall(optr: optr!)
+    }
+
+    public func rescanTransactions(details: [String: Any]) throws -> TwoFactorCall {
+        var optr: OpaquePointer? = nil
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        try callWrapper(fun: GA_rescan_transactions(session, details_json, &optr))
+        defer {
+            GA_destroy_json(details_json)
+        }
+        return TwoFactorCall(optr: optr!)
+    }
+
+    public func getSyncStatus() throws -> [String: Any]? {
+        var result: OpaquePointer? = nil
+        try callWrapper(fun: GA_get_sync_status(session, &result))
+        return try convertOpaqueJsonToDict(o: result!)
+    }
+
+    public func exportUnblindedOutputs(details: [String: Any]) throws -> TwoFactorCall {
+        var optr: OpaquePointer? = nil
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        try callWrapper(fun: GA_export_unblinded_outputs(session, details_json, &optr))
+        defer {
+            GA_destroy_json(details_json)
+        }
+        return TwoFactorCall(optr: optr!)
+    }
+
 
     public func getAvailableCurrencies() throws -> [String: Any]? {
         var result: OpaquePointer? = nil
@@ -368,6 +501,18 @@ public class Session {
         return try jsonFuncToJsonWrapper(input: input, fun: GA_convert_amount)
     }
 
+    public func parseAmount(input: [String: Any]) throws -> [String: Any]? {
+        return try jsonFuncToJsonWrapper(input: input, fun: GA_parse_amount)
+    }
+
+    public func parseUri(input: [String: Any]) throws -> [String: Any]? {
+        return try jsonFuncToJsonWrapper(input: input, fun: GA_parse_uri)
+    }
+
+    public func parseAddress(input: [String: Any]) throws -> [String: Any]? {
+        return try jsonFuncToJsonWrapper(input: input, fun: GA_parse_address)
+    }
+
     public func createTransaction(details: [String: Any]) throws -> TwoFactorCall {
         var optr: OpaquePointer? = nil
         var details_json: OpaquePointer = try convertDictToJSON(dict: details)
@@ -388,6 +533,10 @@ public class Session {
         return TwoFactorCall(optr: optr!);
     }
 
+    public func canSignTransaction(details: [String: Any]) throws -> [String: Any]? {
+        return try jsonFuncToJsonWrapper(input: details, fun: GA_can_sign_transaction)
+    }
+
     public func sendTransaction(details: [String: Any]) throws -> TwoFactorCall {
         var optr: OpaquePointer? = nil;
         var details_json: OpaquePointer = try convertDictToJSON(dict: details)
@@ -442,6 +591,26 @@ public class Session {
         return TwoFactorCall(optr: optr!);
     }
 
+    public func signMessage(details: [String: Any]) throws -> TwoFactorCall {
+        var optr: OpaquePointer? = nil
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        try callWrapper(fun: GA_sign_message(session, details_json, &optr))
+        defer {
+            GA_destroy_json(details_json)
+        }
+        return TwoFactorCall(optr: optr!)
+    }
+
+    public func verifyMessage(details: [String: Any]) throws -> Bool {
+        var result: UInt32 = 0
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        defer {
+            GA_destroy_json(details_json)
+        }
+        try callWrapper(fun: GA_verify_message(session, details_json, &result))
+        return result == GA_TRUE
+    }
+
     public func changeSettings(details: [String: Any]) throws -> TwoFactorCall {
         var optr: OpaquePointer? = nil;
         var details_json: OpaquePointer = try convertDictToJSON(dict: details)
@@ -490,12 +659,88 @@ public class Session {
         return try convertOpaqueJsonToDict(o: result!)
     }
 
+    public func getTransactionBlinders(txhash: String) throws -> [String: Any]? {
+        var result: OpaquePointer? = nil
+        try callWrapper(fun: GA_get_transaction_blinders(session, txhash, &result))
+        return try convertOpaqueJsonToDict(o: result!)
+    }
+
+    public func decodeTransaction(details: [String: Any]) throws -> [String: Any]? {
+        return try jsonFuncToJsonWrapper(input: details, fun: GA_decode_transaction)
+    }
+
     public func getFeeEstimates() throws -> [String: Any]? {
         var result: OpaquePointer? = nil
         try callWrapper(fun: GA_get_fee_estimates(session, &result))
         return try convertOpaqueJsonToDict(o: result!)
     }
 
+    public func estimateTransactionFee(details: [String: Any]) throws -> [String: Any]? {
+        return try jsonFuncToJsonWrapper(input: details, fun: GA_estimate_transaction_fee)
+    }
+
+    public func getFeeBumpOptions(details: [String: Any]) throws -> [String: Any]? {
+        return try jsonFuncToJsonWrapper(input: details, fun: GA_get_fee_bump_options)
+    }
+
+    public func getNetworkParameters() throws -> [String: Any]? {
+        var result: OpaquePointer? = nil
+        try callWrapper(fun: GA_get_network_parameters(session, &result))
+        return try convertOpaqueJsonToDict(o: result!)
+    }
+
+    public func getEventJournal() throws -> [String: Any]? {
+        var result: OpaquePointer? = nil
+        try callWrapper(fun: GA_get_event_journal(session, &result))
+        return try convertOpaqueJsonToDict(o: result!)
+    }
+
+    public func getCacheStats() throws -> [String: Any]? {
+        var result: OpaquePointer? = nil
+        try callWrapper(fun: GA_get_cache_stats(session, &result))
+        return try convertOpaqueJsonToDict(o: result!)
+    }
+
+    public func getDenylist() throws -> [String: Any]? {
+        var result: OpaquePointer? = nil
+        try callWrapper(fun: GA_get_denylist(session, &result))
+        return try convertOpaqueJsonToDict(o: result!)
+    }
+
+    public func setDenylist(denylist: [String: Any]) throws -> Void {
+        var denylist_json: OpaquePointer = try convertDictToJSON(dict: denylist)
+        defer {
+            GA_destroy_json(denylist_json)
+        }
+        try callWrapper(fun: GA_set_denylist(session, denylist_json))
+    }
+
+    public func getAddressLabels(details: [String: Any]) throws -> [String: Any]? {
+        return try jsonFuncToJsonWrapper(input: details, fun: GA_get_address_labels)
+    }
+
+    public func setAddressLabel(details: [String: Any]) throws -> Void {
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        defer {
+            GA_destroy_json(details_json)
+        }
+        try callWrapper(fun: GA_set_address_label(session, details_json))
+    }
+
+    public func getTransactionTemplates() throws -> [String: Any]? {
+        var result: OpaquePointer? = nil
+        try callWrapper(fun: GA_get_transaction_templates(session, &result))
+        return try convertOpaqueJsonToDict(o: result!)
+    }
+
+    public func setTransactionTemplate(details: [String: Any]) throws -> Void {
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        defer {
+            GA_destroy_json(details_json)
+        }
+        try callWrapper(fun: GA_set_transaction_template(session, details_json))
+    }
+
     public func getMnemonicPassphrase(password: String) throws -> String {
         var buff: UnsafeMutablePointer<Int8>? = nil
         try callWrapper(fun: GA_get_mnemonic_passphrase(session, password, &buff))
@@ -513,6 +758,18 @@ public class Session {
         return try jsonFuncToJsonWrapper(input: params, fun: GA_refresh_assets)
     }
 
+    public func lookupAsset(params: [String: Any]) throws -> [String: Any]? {
+        return try jsonFuncToJsonWrapper(input: params, fun: GA_lookup_asset)
+    }
+
+    public func importBlinders(details: [String: Any]) throws -> Void {
+        var details_json: OpaquePointer = try convertDictToJSON(dict: details)
+        defer {
+            GA_destroy_json(details_json)
+        }
+        try callWrapper(fun: GA_import_blinders(session, details_json))
+    }
+
     public func validateAssetDomainName(params: [String: Any]) throws -> [String: Any]? {
         return try jsonFuncToJsonWrapper(input: params, fun: GA_validate_asset_domain_name)
     }
@@ -529,12 +786,29 @@ public func generateMnemonic() throws -> String {
     return String(cString: buff!)
 }
 
+public func generateMnemonic12() throws -> String {
+    var buff : UnsafeMutablePointer<Int8>? = nil
+    guard GA_generate_mnemonic_12(&buff) == GA_OK else {
+        throw GaError.GenericError
+    }
+    defer {
+        GA_destroy_string(buff)
+    }
+    return String(cString: buff!)
+}
+
 public func validateMnemonic(mnemonic: String) throws -> Bool {
     var result: UInt32 = 0
     try callWrapper(fun: GA_validate_mnemonic(mnemonic, &result))
     return result == GA_TRUE
 }
 
+public func checkMnemonic(mnemonic: String) throws -> [String: Any]? {
+    var result: OpaquePointer? = nil
+    try callWrapper(fun: GA_check_mnemonic(mnemonic, &result))
+    return try convertOpaqueJsonToDict(o: result!)
+}
+
 public func registerNetwork(name: String, details: [String: Any]) throws -> Void {
     var details_json: OpaquePointer = try convertDictToJSON(dict: details)
     defer {
@@ -476,9 +476,13 @@ LOCALFUNC jbyteArray create_array(JNIEnv *jenv, const unsigned char* p, size_t l
 
 %internal_returns_void__(GA_init)
 %returns_struct(GA_ack_system_message, GA_auth_handler)
+%returns_struct(GA_sign_message, GA_auth_handler)
 %returns_string(GA_broadcast_transaction)
 %returns_void__(GA_connect)
 %returns_struct(GA_convert_amount, GA_json)
+%returns_struct(GA_parse_amount, GA_json)
+%returns_struct(GA_parse_uri, GA_json)
+%returns_struct(GA_parse_address, GA_json)
 %returns_string(GA_convert_json_to_string)
 %returns_string(GA_convert_json_value_to_string)
 %returns_struct(GA_convert_string_to_json, GA_json)
@@ -490,30 +494,57 @@ LOCALFUNC jbyteArray create_array(JNIEnv *jenv, const unsigned char* p, size_t l
 %returns_void__(GA_destroy_json)
 %returns_void__(GA_disconnect)
 %returns_void__(GA_reconnect_hint)
+%returns_void__(GA_cancel_pending_requests)
 %returns_string(GA_get_tor_socks5)
 %returns_struct(GA_http_get, GA_json)
 %returns_struct(GA_refresh_assets, GA_json)
+%returns_struct(GA_lookup_asset, GA_json)
 %returns_struct(GA_validate_asset_domain_name, GA_json)
 %returns_string(GA_generate_mnemonic)
+%returns_string(GA_generate_mnemonic_12)
 %returns_struct(GA_get_available_currencies, GA_json)
 %returns_struct(GA_get_balance, GA_auth_handler)
+%returns_struct(GA_get_balance_detailed, GA_auth_handler)
+%returns_struct(GA_get_balance_history, GA_auth_handler)
+%returns_struct(GA_get_spending_summary, GA_auth_handler)
+%returns_struct(GA_get_generated_addresses, GA_auth_handler)
+%returns_struct(GA_rescan_transactions, GA_auth_handler)
+%returns_struct(GA_get_sync_status, GA_json)
+%returns_struct(GA_export_unblinded_outputs, GA_auth_handler)
 %returns_struct(GA_get_fee_estimates, GA_json)
+%returns_struct(GA_estimate_transaction_fee, GA_json)
+%returns_struct(GA_get_fee_bump_options, GA_json)
 %returns_string(GA_get_mnemonic_passphrase)
 %returns_struct(GA_get_networks, GA_json)
+%returns_struct(GA_get_network_parameters, GA_json)
+%returns_struct(GA_get_event_journal, GA_json)
+%returns_struct(GA_get_cache_stats, GA_json)
+%returns_struct(GA_get_denylist, GA_json)
+%returns_struct(GA_get_address_labels, GA_json)
 %returns_array_(GA_get_random_bytes, 2, 3, jarg1)
 %returns_uint32(GA_get_uniform_uint32_t)
 %returns_struct(GA_get_transaction_details, GA_json)
+%returns_struct(GA_get_transaction_blinders, GA_json)
+%returns_struct(GA_decode_transaction, GA_json)
 %returns_struct(GA_get_subaccounts, GA_auth_handler)
 %returns_struct(GA_get_subaccount, GA_auth_handler)
 %returns_void__(GA_rename_subaccount)
+%returns_void__(GA_set_subaccount_hidden)
 %returns_string(GA_get_system_message)
 %returns_struct(GA_get_transactions, GA_auth_handler)
 %returns_struct(GA_get_twofactor_config, GA_json)
 %returns_struct(GA_get_unspent_outputs, GA_auth_handler)
 %returns_struct(GA_get_unspent_outputs_for_private_key, GA_json)
+%returns_void__(GA_freeze_utxo)
+%returns_void__(GA_unfreeze_utxo)
+%returns_struct(GA_get_utxo_policy, GA_json)
+%returns_void__(GA_set_utxo_policy)
 %returns_struct(GA_get_receive_address, GA_auth_handler)
+%returns_struct(GA_get_receive_payload, GA_auth_handler)
 %returns_void__(GA_login_watch_only)
 %returns_void__(GA_login_with_pin)
+%returns_void__(GA_set_cache_encryption_key)
+%returns_void__(GA_change_cache_password)
 %returns_struct(GA_login, GA_auth_handler)
 %returns_void__(GA_register_network)
 %returns_struct(GA_register_user, GA_auth_handler)
@@ -527,8 +558,14 @@ LOCALFUNC jbyteArray create_array(JNIEnv *jenv, const unsigned char* p, size_t l
 %returns_void__(GA_disable_all_pin_logins)
 %returns_void__(GA_set_transaction_memo)
 %returns_void__(GA_set_watch_only)
+%returns_void__(GA_set_denylist)
+%returns_void__(GA_import_blinders)
+%returns_void__(GA_set_address_label)
+%returns_struct(GA_get_transaction_templates, GA_json)
+%returns_void__(GA_set_transaction_template)
 %returns_string(GA_get_watch_only_username)
 %returns_struct(GA_sign_transaction, GA_auth_handler)
+%returns_struct(GA_can_sign_transaction, GA_json)
 %returns_void__(GA_auth_handler_call)
 %returns_struct(GA_twofactor_cancel_reset, GA_auth_handler)
 %returns_struct(GA_twofactor_reset, GA_auth_handler)
@@ -540,6 +577,8 @@ LOCALFUNC jbyteArray create_array(JNIEnv *jenv, const unsigned char* p, size_t l
 %returns_void__(GA_auth_handler_request_code)
 %returns_void__(GA_auth_handler_resolve_code)
 %returns_uint32(GA_validate_mnemonic)
+%returns_struct(GA_check_mnemonic, GA_json)
+%returns_uint32(GA_verify_message)
 
 /* TODO
 GA_convert_json_value_to_bool
@@ -42,6 +42,9 @@ static int check_result(int result)
     case GA_NOT_AUTHORIZED:
         PyErr_SetString(PyExc_RuntimeError, "Not Authorized");
         break;
+    case GA_CANCELLED:
+        PyErr_SetString(PyExc_RuntimeError, "Cancelled");
+        break;
     default: /* FIXME */
         PyErr_SetString(PyExc_RuntimeError, "Connection Error");
         break;
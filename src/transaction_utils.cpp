@@ -9,8 +9,11 @@
 #include "utils.hpp"
 #include "xpub_hdkey.hpp"
 
+#include <array>
 #include <cctype>
 
+#include <websocketpp/base64/base64.hpp>
+
 namespace {
 bool isupper(const std::string& s)
 {
@@ -19,6 +22,79 @@ bool isupper(const std::string& s)
 
 using namespace ga::sdk;
 
+// bech32 checksum constants, see BIP-173 and BIP-350
+constexpr uint32_t BECH32_CONST = 1;
+constexpr uint32_t BECH32M_CONST = 0x2bc830a3;
+
+uint32_t bech32_polymod(const std::vector<unsigned char>& values)
+{
+    static const std::array<uint32_t, 5> GEN = { { 0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3 } };
+    uint32_t chk = 1;
+    for (const auto v : values) {
+        const uint32_t top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ v;
+        for (size_t i = 0; i < GEN.size(); ++i) {
+            chk ^= ((top >> i) & 1) != 0 ? GEN[i] : 0;
+        }
+    }
+    return chk;
+}
+
+// Decode a segwit address of any witness version into its scriptpubkey.
+// Versions above 0 use the bech32m checksum from BIP-350, which the wally
+// version we build against does not support.
+std::vector<unsigned char> addr_segwit_to_script(const std::string& address, const std::string& family)
+{
+    static const std::string CHARSET("qpzry9x8gf2tvdw0s3jn54khce6mua7l");
+
+    const auto sep = address.rfind('1');
+    GDK_RUNTIME_ASSERT(sep != std::string::npos && address.size() <= 90 && sep + 8 <= address.size());
+    GDK_RUNTIME_ASSERT(address.substr(0, sep) == family);
+
+    std::vector<unsigned char> values;
+    for (const char c : family) {
+        values.push_back(static_cast<unsigned char>(c) >> 5);
+    }
+    values.push_back(0);
+    for (const char c : family) {
+        values.push_back(static_cast<unsigned char>(c) & 31);
+    }
+    std::vector<unsigned char> data;
+    for (size_t i = sep + 1; i < address.size(); ++i) {
+        const auto pos = CHARSET.find(address[i]);
+        GDK_RUNTIME_ASSERT(pos != std::string::npos); // Also rejects uppercase
+        data.push_back(static_cast<unsigned char>(pos));
+    }
+    values.insert(values.end(), data.begin(), data.end());
+
+    const uint32_t version = data.front();
+    GDK_RUNTIME_ASSERT(version <= 16);
+    GDK_RUNTIME_ASSERT(bech32_polymod(values) == (version == 0 ? BECH32_CONST : BECH32M_CONST));
+
+    // Convert the program from 5 to 8 bit groups, without padding
+    std::vector<unsigned char> program;
+    uint32_t acc = 0;
+    size_t bits = 0;
+    for (auto p = data.begin() + 1; p != data.end() - 6; ++p) {
+        acc = (acc << 5) | *p;
+        bits += 5;
+        if (bits >= 8) {
+            bits -= 8;
+            program.push_back(static_cast<unsigned char>((acc >> bits) & 0xff));
+        }
+    }
+    GDK_RUNTIME_ASSERT(bits < 5 && ((acc << (8 - bits)) & 0xff) == 0);
+    GDK_RUNTIME_ASSERT(program.size() >= 2 && program.size() <= 40);
+    GDK_RUNTIME_ASSERT(version != 0 || program.size() == HASH160_LEN || program.size() == SHA256_LEN);
+
+    std::vector<unsigned char> script;
+    script.reserve(program.size() + 2);
+    script.push_back(version == 0 ? OP_0 : static_cast<unsigned char>(OP_1 + version - 1));
+    script.push_back(static_cast<unsigned char>(program.size()));
+    script.insert(script.end(), program.begin(), program.end());
+    return script;
+}
+
 std::vector<unsigned char> output_script_for_address(
     const network_parameters& net_params, std::string address, std::string& error)
 {
@@ -43,6 +119,10 @@ std::vector<unsigned char> output_script_for_address(
         }
     }
 
+    if (is_bech32 && !net_params.liquid()) {
+        // Allow paying to any witness version, e.g. taproot (v1) outputs
+        return addr_segwit_to_script(address, net_params.bech32_prefix());
+    }
     if (is_bech32 || is_blech32) {
         return addr_segwit_v0_to_bytes(address, net_params.bech32_prefix());
     }
@@ -73,6 +153,14 @@ std::vector<unsigned char> output_script_for_address(
         error = res::id_invalid_address;
     }
 
+    if (error == res::id_nonconfidential_addresses_not
+        && json_get_value(result, "allow_unconfidential_addressees", false)) {
+        // The caller has explicitly asked to send to unconfidential addresses.
+        // An empty script means a base58 version byte for another network,
+        // which would otherwise be paid to an empty script, i.e. as fee
+        error = script.empty() ? res::id_invalid_address : std::string();
+    }
+
     if (!error.empty()) {
         // Overwite any existing error in the transaction as addressees
         // are entered and should be corrected first.
@@ -84,6 +172,19 @@ std::vector<unsigned char> output_script_for_address(
 
     return script;
 }
+
+// Whether an address is a valid, unconfidential Liquid address
+bool is_unconfidential_address(const network_parameters& net_params, const std::string& address)
+{
+    std::string error;
+    std::vector<unsigned char> script;
+    try {
+        script = output_script_for_address(net_params, address, error);
+    } catch (const std::exception& e) {
+        return false;
+    }
+    return !script.empty() && error == res::id_nonconfidential_addresses_not;
+}
 } // namespace
 
 namespace ga {
@@ -181,6 +282,170 @@ namespace sdk {
         return script;
     }
 
+    std::vector<unsigned char> scriptpubkey_from_address(
+        const network_parameters& net_params, const std::string& address)
+    {
+        std::string error; // Ignored, e.g. non-confidential addresses are allowed on Liquid
+        std::vector<unsigned char> script;
+        try {
+            script = output_script_for_address(net_params, address, error);
+        } catch (const std::exception&) {
+            script.clear();
+        }
+        if (script.empty()) {
+            throw user_error(res::id_invalid_address);
+        }
+        return script;
+    }
+
+    std::string get_output_type(byte_span_t script)
+    {
+        const auto size = script.size();
+        if (size == 0) {
+            return "fee"; // Liquid fee outputs have an empty script
+        }
+        if (script[0] == OP_RETURN) {
+            return "op_return";
+        }
+        if (size == 25 && script[0] == OP_DUP && script[1] == OP_HASH160 && script[2] == HASH160_LEN
+            && script[23] == OP_EQUALVERIFY && script[24] == OP_CHECKSIG) {
+            return "p2pkh";
+        }
+        if (size == 23 && script[0] == OP_HASH160 && script[1] == HASH160_LEN && script[22] == OP_EQUAL) {
+            return "p2sh";
+        }
+        const bool is_witness_version = script[0] == OP_0 || (script[0] >= OP_1 && script[0] <= OP_16);
+        if (is_witness_version && size >= 4 && size <= 42 && script[1] == size - 2) {
+            if (script[0] == OP_0 && size == 2 + HASH160_LEN) {
+                return "p2wpkh";
+            }
+            if (script[0] == OP_0 && size == 2 + SHA256_LEN) {
+                return "p2wsh";
+            }
+            if (script[0] == OP_1 && size == 2 + SHA256_LEN) {
+                return "p2tr";
+            }
+            return "segwit";
+        }
+        return "unknown";
+    }
+
+    nlohmann::json parse_address(const network_parameters& net_params, const std::string& address)
+    {
+        nlohmann::json result = { { "address", address }, { "is_valid", false }, { "error", std::string() },
+            { "reason", std::string() } };
+
+        std::string error;
+        std::vector<unsigned char> script;
+        try {
+            script = output_script_for_address(net_params, address, error);
+        } catch (const std::exception&) {
+            script.clear();
+        }
+
+        if (!script.empty()) {
+            result["scriptpubkey"] = b2h(script);
+            result["output_type"] = get_output_type(script);
+            if (net_params.liquid()) {
+                result["is_confidential"] = error.empty();
+            }
+            if (!error.empty()) {
+                // Only payable when the caller allows unconfidential addressees
+                result["error"] = error;
+                result["reason"] = "unconfidential";
+            }
+            result["is_valid"] = error.empty();
+            return result;
+        }
+
+        result["error"] = res::id_invalid_address;
+        result["reason"] = "malformed";
+        if (net_params.liquid() && boost::algorithm::starts_with(address, net_params.bech32_prefix())) {
+            try {
+                // Decodes, but isn't a version 0 program as Liquid requires
+                addr_segwit_to_script(address, net_params.bech32_prefix());
+                result["reason"] = "unsupported_witness_version";
+                return result;
+            } catch (const std::exception&) {
+            }
+        }
+
+        // Check whether the address belongs to another network, so
+        // callers can explain the mistake rather than just rejecting it
+        const auto networks = network_parameters::get_all();
+        for (const auto& name : networks.at("all_networks")) {
+            const network_parameters other(networks.at(name.get<std::string>()));
+            if (other.network() == net_params.network()) {
+                continue;
+            }
+            try {
+                std::string ignored;
+                if (!output_script_for_address(other, address, ignored).empty()) {
+                    result["reason"] = "wrong_network";
+                    result["network"] = name;
+                    break;
+                }
+            } catch (const std::exception&) {
+            }
+        }
+        return result;
+    }
+
+    std::string get_message_signature(byte_span_t message_hash, byte_span_t public_key, byte_span_t sig)
+    {
+        GDK_RUNTIME_ASSERT(sig.size() == EC_SIGNATURE_LEN);
+        std::vector<unsigned char> recoverable(1 + EC_SIGNATURE_LEN);
+        std::copy(sig.begin(), sig.end(), recoverable.begin() + 1);
+        for (unsigned char recovery_id = 0; recovery_id < 4; ++recovery_id) {
+            recoverable[0] = 27 + 4 + recovery_id; // The header for a compressed public key
+            try {
+                const auto recovered = ec_sig_to_public_key(message_hash, recoverable);
+                if (std::equal(recovered.begin(), recovered.end(), public_key.begin(), public_key.end())) {
+                    return websocketpp::base64_encode(recoverable.data(), recoverable.size());
+                }
+            } catch (const std::exception&) {
+                // Not recoverable with this recovery id
+            }
+        }
+        GDK_RUNTIME_ASSERT_MSG(false, "invalid signature");
+        __builtin_unreachable();
+    }
+
+    std::vector<unsigned char> get_message_signature_public_key(byte_span_t message_hash, const std::string& signature)
+    {
+        const auto decoded = websocketpp::base64_decode(signature);
+        std::vector<unsigned char> recoverable(decoded.begin(), decoded.end());
+        GDK_RUNTIME_ASSERT_MSG(recoverable.size() == 1 + EC_SIGNATURE_LEN, "invalid signature");
+        // Headers 27-30 are for uncompressed keys, 31-34 compressed and 35-42
+        // segwit (BIP137). Only the recovery id is needed to recover the key
+        const unsigned char header = recoverable[0];
+        GDK_RUNTIME_ASSERT_MSG(header >= 27 && header <= 42, "invalid signature");
+        recoverable[0] = 27 + ((header - 27) & 3);
+        const auto public_key = ec_sig_to_public_key(message_hash, recoverable);
+        return header < 31 ? ec_public_key_decompress(public_key) : public_key;
+    }
+
+    bool is_public_key_address(
+        const network_parameters& net_params, const std::string& address, byte_span_t public_key)
+    {
+        std::vector<unsigned char> script;
+        try {
+            script = scriptpubkey_from_address(net_params, address);
+        } catch (const std::exception&) {
+            return false;
+        }
+        const auto pubkey_hash = hash160(public_key);
+        if (script == scriptpubkey_p2pkh_from_hash160(pubkey_hash)) {
+            return true;
+        }
+        if (public_key.size() != EC_PUBLIC_KEY_LEN) {
+            return false; // Segwit requires compressed public keys
+        }
+        std::vector<unsigned char> p2wpkh = { OP_0, HASH160_LEN };
+        p2wpkh.insert(p2wpkh.end(), pubkey_hash.begin(), pubkey_hash.end());
+        return script == p2wpkh || script == scriptpubkey_p2sh_from_hash160(hash160(p2wpkh));
+    }
+
     std::vector<unsigned char> output_script_from_utxo(const network_parameters& net_params, ga_pubkeys& pubkeys,
         ga_user_pubkeys& user_pubkeys, ga_user_pubkeys& recovery_pubkeys, const nlohmann::json& utxo)
     {
@@ -265,6 +530,85 @@ namespace sdk {
         return amount(rounded_fee);
     }
 
+    // Serialized sizes of each input type when signed, as {non-witness, witness} bytes.
+    // Signatures are assumed to be a low-R user sig and a high-R GA sig.
+    static const std::map<std::string, std::pair<size_t, size_t>> INPUT_SIZES{ { "p2pkh", { 148, 0 } },
+        { "p2sh", { 261, 0 } }, { "p2wsh", { 76, 221 } }, { "csv", { 76, 227 } } };
+
+    // Serialized sizes of each output script type
+    static const std::map<std::string, size_t> OUTPUT_SCRIPT_SIZES{ { "p2pkh", 25 }, { "p2sh", 23 },
+        { "p2wpkh", 22 }, { "p2wsh", 34 }, { "p2tr", 34 } };
+
+    static size_t varint_size(size_t n) { return n < 0xfd ? 1 : n <= 0xffff ? 3 : n <= 0xffffffff ? 5 : 9; }
+
+    // Size of an Elements rangeproof for a value with 'bits' mantissa bits
+    static size_t rangeproof_size(size_t bits)
+    {
+        const size_t rings = (bits + 1) / 2;
+        return 10 + 32 + (rings - 1) * 32 + (rings - 1 + 7) / 8 + rings * 4 * 32;
+    }
+
+    size_t estimate_tx_weight(
+        const network_parameters& net_params, const nlohmann::json& inputs, const nlohmann::json& outputs)
+    {
+        const bool is_liquid = net_params.liquid();
+
+        size_t num_inputs = 0, num_outputs = 0, base_size = 0, witness_size = 0;
+        bool has_witness = false;
+        for (const auto& input : inputs.items()) {
+            const auto p = INPUT_SIZES.find(input.key());
+            GDK_RUNTIME_ASSERT_MSG(p != INPUT_SIZES.end(), "unknown input type " + input.key());
+            const size_t count = input.value();
+            num_inputs += count;
+            base_size += count * p->second.first;
+            // Every input has a witness stack count in a segwit tx, even if empty
+            witness_size += count * (p->second.second != 0 ? p->second.second : 1);
+            has_witness |= count != 0 && p->second.second != 0;
+            if (is_liquid) {
+                // Empty issuance rangeproofs and pegin witness
+                witness_size += count * 3;
+            }
+        }
+        GDK_RUNTIME_ASSERT_MSG(num_inputs != 0, "at least one input is required");
+
+        const size_t surjectionproof_size = is_liquid ? asset_surjectionproof_size(num_inputs) : 0;
+        const size_t proof_size = is_liquid ? rangeproof_size(net_params.ct_bits()) : 0;
+
+        for (const auto& output : outputs.items()) {
+            const auto p = OUTPUT_SCRIPT_SIZES.find(output.key());
+            GDK_RUNTIME_ASSERT_MSG(p != OUTPUT_SCRIPT_SIZES.end(), "unknown output type " + output.key());
+            const size_t count = output.value();
+            num_outputs += count;
+            if (is_liquid) {
+                // Confidential asset, value and nonce commitments along with their proofs
+                base_size += count * (33 + 33 + 33 + 1 + p->second);
+                witness_size += count
+                    * (varint_size(surjectionproof_size) + surjectionproof_size + varint_size(proof_size) + proof_size);
+            } else {
+                base_size += count * (8 + 1 + p->second);
+            }
+        }
+        if (is_liquid) {
+            // Explicit fee output with empty proofs
+            ++num_outputs;
+            base_size += 33 + 9 + 1 + 1;
+            witness_size += 2;
+            has_witness = true;
+        }
+        GDK_RUNTIME_ASSERT_MSG(num_outputs != 0, "at least one output is required");
+
+        // Version, locktime and input/output counts
+        base_size += 4 + 4 + varint_size(num_inputs) + varint_size(num_outputs);
+        if (is_liquid) {
+            base_size += 1; // Witness flag
+        } else if (has_witness) {
+            witness_size += 2; // Segwit marker and flag
+        } else {
+            witness_size = 0;
+        }
+        return base_size * 4 + witness_size;
+    }
+
     amount add_tx_output(const network_parameters& net_params, nlohmann::json& result, wally_tx_ptr& tx,
         const std::string& address, amount::value_type satoshi, const std::string& asset_tag)
     {
@@ -298,9 +642,93 @@ namespace sdk {
         tx_elements_output_commitment_set(tx, index, asset_bytes, ct_value, {}, {}, {});
     }
 
+    // Add an output paying to a raw script, for scripts the address parser doesn't support
+    static amount add_tx_script_addressee(ga_session& session, const network_parameters& net_params,
+        nlohmann::json& result, wally_tx_ptr& tx, nlohmann::json& addressee)
+    {
+        std::vector<unsigned char> script;
+        if (json_get_value(result, "allow_scriptpubkeys", false) && !net_params.liquid()) {
+            try {
+                script = h2b(addressee.at("scriptpubkey"));
+            } catch (const std::exception&) {
+                script.clear();
+            }
+        }
+        if (script.empty()) {
+            // Not enabled, unsupported on Liquid since outputs must be blinded, or invalid
+            result["error"] = res::id_invalid_address;
+            // Create a dummy script so that the caller gets back a reasonable fee estimate
+            script.assign(HASH160_LEN, 0);
+        }
+
+        amount satoshi;
+        try {
+            satoshi = session.convert_amount(addressee)["satoshi"].get<amount::value_type>();
+        } catch (const std::exception&) {
+            result["error"] = res::id_invalid_amount;
+        }
+
+        // Outputs below the dust threshold are not relayed, except for unspendable OP_RETURN outputs
+        const bool is_op_return = script.front() == OP_RETURN;
+        if (!is_op_return && !result.value("send_all", false) && satoshi.value() < session.get_dust_threshold()) {
+            result["error"] = res::id_invalid_amount;
+        }
+
+        amount::strip_non_satoshi_keys(addressee);
+        addressee["satoshi"] = satoshi.value(); // Sets to 0 if not present
+
+        if (session.is_denied_script(script)) {
+            addressee["is_denied"] = true;
+            result["error"] = res::id_invalid_address;
+        }
+
+        tx_add_raw_output(tx, satoshi.value(), script);
+        return satoshi;
+    }
+
+    // Add a zero value OP_RETURN output carrying data, e.g. to anchor a commitment
+    static amount add_tx_op_return_addressee(
+        const network_parameters& net_params, nlohmann::json& result, wally_tx_ptr& tx, nlohmann::json& addressee)
+    {
+        constexpr size_t max_data_len = 80; // The standardness limit
+        std::vector<unsigned char> data;
+        bool valid = !net_params.liquid(); // Liquid outputs must be blinded
+        try {
+            data = h2b(addressee.at("op_return"));
+        } catch (const std::exception&) {
+            valid = false;
+        }
+        if (!valid || data.size() > max_data_len) {
+            result["error"] = res::id_invalid_address;
+            data.resize(std::min(data.size(), max_data_len));
+        }
+
+        std::vector<unsigned char> script{ OP_RETURN };
+        if (data.size() > 75) {
+            script.push_back(OP_PUSHDATA1);
+        }
+        if (!data.empty()) {
+            script.push_back(static_cast<unsigned char>(data.size()));
+            script.insert(script.end(), data.begin(), data.end());
+        }
+
+        amount::strip_non_satoshi_keys(addressee);
+        addressee["satoshi"] = 0;
+        addressee["scriptpubkey"] = b2h(script);
+        tx_add_raw_output(tx, 0, script);
+        return amount();
+    }
+
     amount add_tx_addressee(ga_session& session, const network_parameters& net_params, nlohmann::json& result,
         wally_tx_ptr& tx, nlohmann::json& addressee)
     {
+        if (addressee.contains("op_return")) {
+            return add_tx_op_return_addressee(net_params, result, tx, addressee);
+        }
+        if (addressee.contains("scriptpubkey")) {
+            return add_tx_script_addressee(session, net_params, result, tx, addressee);
+        }
+
         std::string address = addressee.at("address"); // Assume its a standard address
 
         nlohmann::json uri_params = parse_bitcoin_uri(address, net_params.bip21_prefix());
@@ -351,8 +779,29 @@ namespace sdk {
         amount::strip_non_satoshi_keys(addressee);
         addressee["satoshi"] = satoshi.value(); // Sets to 0 if not present
 
-        return add_tx_output(
+        if (net_params.liquid() && is_unconfidential_address(net_params, address)) {
+            // Paid with an explicit asset and value, see output_script_for_address
+            addressee["is_unconfidential"] = true;
+        }
+
+        const auto ret = add_tx_output(
             net_params, result, tx, address, satoshi.value(), addressee.value("asset_tag", std::string{}));
+
+        // Refuse to pay addresses the user has denied
+        if (session.is_denied_address(address)) {
+            addressee["is_denied"] = true;
+            result["error"] = res::id_invalid_address;
+        }
+        return ret;
+    }
+
+    std::string get_txhash(const wally_tx_ptr& tx) { return b2h_rev(sha256d(tx_to_bytes(tx, 0))); }
+
+    std::string get_wtxid(const wally_tx_ptr& tx)
+    {
+        // Elements commits to witness data differently, via a merkle root
+        GDK_RUNTIME_ASSERT(!tx_is_elements(tx));
+        return b2h_rev(sha256d(tx_to_bytes(tx, WALLY_TX_FLAG_USE_WITNESS)));
     }
 
     void update_tx_info(const wally_tx_ptr& tx, nlohmann::json& result)
@@ -361,6 +810,7 @@ namespace sdk {
         result["transaction"] = valid ? b2h(tx_to_bytes(tx)) : std::string();
         const auto weight = tx_get_weight(tx);
         result["transaction_size"] = valid ? tx_get_length(tx, WALLY_TX_FLAG_USE_WITNESS) : 0;
+        result["transaction_stripped_size"] = valid ? tx_get_length(tx, 0) : 0;
         result["transaction_weight"] = valid ? weight : 0;
         const uint32_t tx_vsize = valid ? tx_vsize_from_weight(weight) : 0;
         result["transaction_vsize"] = tx_vsize;
@@ -368,6 +818,11 @@ namespace sdk {
         result["transaction_locktime"] = tx->locktime;
         const bool is_liquid = tx_is_elements(tx);
         result["liquid"] = is_liquid;
+        // Note the txid of an unsigned tx changes on signing if it has non-segwit inputs
+        result["txhash"] = valid ? get_txhash(tx) : std::string();
+        if (!is_liquid) {
+            result["transaction_wtxid"] = valid ? get_wtxid(tx) : std::string();
+        }
         if (result.find("fee") != result.end()) {
             if (is_liquid) {
                 result["calculated_fee_rate"] = result["fee"];
@@ -487,13 +942,16 @@ namespace sdk {
                     const auto& addressee = result.at("addressees").at(addressee_index);
                     const auto& address = addressee.at("address");
                     output["address"] = address;
-                    if (net_params.liquid()) {
+                    if (json_get_value(addressee, "is_unconfidential", false)) {
+                        output["is_unconfidential"] = true;
+                    } else if (net_params.liquid()) {
                         output["public_key"] = blinding_key_from_addr(address);
                     }
                     ++addressee_index;
                 }
 
-                if (net_params.liquid() && !is_fee && !output.contains("eph_keypair_sec")) {
+                if (net_params.liquid() && !is_fee && !output.contains("eph_keypair_sec")
+                    && !output.contains("is_unconfidential")) {
                     auto ephemeral_keypair = get_ephemeral_keypair();
                     output["eph_keypair_sec"] = b2h(ephemeral_keypair.first);
                     output["eph_keypair_pub"] = b2h(ephemeral_keypair.second);
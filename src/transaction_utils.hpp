@@ -41,6 +41,28 @@ namespace sdk {
     std::string get_address_from_script(
         const network_parameters& net_params, byte_span_t script, const std::string& addr_type);
 
+    // Get the scriptpubkey an address pays to. Throws if the address is invalid
+    std::vector<unsigned char> scriptpubkey_from_address(
+        const network_parameters& net_params, const std::string& address);
+
+    // Get the standard type of an output script, e.g. "p2wpkh" or "op_return"
+    std::string get_output_type(byte_span_t script);
+
+    // Parse an address, returning its details or why it can't be paid to
+    nlohmann::json parse_address(const network_parameters& net_params, const std::string& address);
+
+    // Encode a signature of a Bitcoin Signed Message hash as a base64 BIP137
+    // compact signature, finding the recovery id that gives public_key
+    std::string get_message_signature(byte_span_t message_hash, byte_span_t public_key, byte_span_t sig);
+
+    // Recover the public key of a base64 BIP137 compact message signature.
+    // Throws if the signature is malformed
+    std::vector<unsigned char> get_message_signature_public_key(byte_span_t message_hash, const std::string& signature);
+
+    // Whether an address pays to public_key alone, i.e. is its p2pkh, p2sh-p2wpkh or p2wpkh address
+    bool is_public_key_address(
+        const network_parameters& net_params, const std::string& address, byte_span_t public_key);
+
     std::vector<unsigned char> output_script_from_utxo(const network_parameters& net_params, ga_pubkeys& pubkeys,
         ga_user_pubkeys& user_pubkeys, ga_user_pubkeys& recovery_pubkeys, const nlohmann::json& utxo);
 
@@ -62,6 +84,10 @@ namespace sdk {
     // Compute the fee for a tx
     amount get_tx_fee(const wally_tx_ptr& tx, amount min_fee_rate, amount fee_rate);
 
+    // Estimate the weight of a tx from the counts of each type of input and output it contains
+    size_t estimate_tx_weight(
+        const network_parameters& net_params, const nlohmann::json& inputs, const nlohmann::json& outputs);
+
     // Add an output to a tx given its address
     amount add_tx_output(const network_parameters& net_params, nlohmann::json& result, wally_tx_ptr& tx,
         const std::string& address, amount::value_type satoshi = 0, const std::string& asset_tag = {});
@@ -78,6 +104,12 @@ namespace sdk {
     vbf_t generate_final_vbf(byte_span_t input_abfs, byte_span_t input_vbfs, uint64_span_t input_values,
         const std::vector<abf_t>& output_abfs, const std::vector<vbf_t>& output_vbfs, uint32_t num_inputs);
 
+    // Get the txid (hash without witness data) of tx, in display order
+    std::string get_txhash(const wally_tx_ptr& tx);
+
+    // Get the wtxid (hash including witness data) of a bitcoin tx, in display order
+    std::string get_wtxid(const wally_tx_ptr& tx);
+
     // Update the json tx representation with info from tx
     void update_tx_info(const wally_tx_ptr& tx, nlohmann::json& result);
 
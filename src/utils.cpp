@@ -8,6 +8,7 @@
 #include <iostream>
 #include <memory>
 #include <mutex>
+#include <numeric>
 
 #ifdef __x86_64
 #include <x86intrin.h>
@@ -233,8 +234,138 @@ namespace sdk {
         return bip39_mnemonic_from_bytes(ciphertext);
     }
 
+    static std::vector<std::string> get_bip39_wordlist(const char* lang)
+    {
+        struct words* wordlist;
+        GDK_VERIFY(bip39_get_wordlist(lang, &wordlist));
+        std::vector<std::string> words;
+        words.reserve(BIP39_WORDLIST_LEN);
+        for (size_t i = 0; i < BIP39_WORDLIST_LEN; ++i) {
+            char* word;
+            GDK_VERIFY(bip39_get_word(wordlist, i, &word));
+            words.emplace_back(word);
+            wally_free_string(word);
+        }
+        return words;
+    }
+
+    static size_t get_edit_distance(const std::string& a, const std::string& b)
+    {
+        std::vector<size_t> row(b.size() + 1);
+        std::iota(row.begin(), row.end(), 0);
+        for (size_t i = 1; i <= a.size(); ++i) {
+            size_t diagonal = row[0];
+            row[0] = i;
+            for (size_t j = 1; j <= b.size(); ++j) {
+                const size_t above = row[j];
+                row[j] = std::min({ row[j] + 1, row[j - 1] + 1, diagonal + (a[i - 1] == b[j - 1] ? 0 : 1) });
+                diagonal = above;
+            }
+        }
+        return row[b.size()];
+    }
+
+    // Return the closest English words to an unknown word, best first
+    static nlohmann::json get_word_suggestions(const std::vector<std::string>& wordlist, const std::string& word)
+    {
+        constexpr size_t max_distance = 2;
+        constexpr size_t max_suggestions = 3;
+        std::vector<std::pair<size_t, std::string>> candidates;
+        for (const auto& candidate : wordlist) {
+            // Words may be abbreviated to their (unique) first four letters
+            const size_t distance
+                = boost::algorithm::starts_with(candidate, word) ? 0 : get_edit_distance(word, candidate);
+            if (distance <= max_distance) {
+                candidates.emplace_back(distance, candidate);
+            }
+        }
+        std::stable_sort(candidates.begin(), candidates.end(),
+            [](const auto& lhs, const auto& rhs) { return lhs.first < rhs.first; });
+        nlohmann::json suggestions = nlohmann::json::array();
+        for (size_t i = 0; i < candidates.size() && i < max_suggestions; ++i) {
+            suggestions.push_back(candidates[i].second);
+        }
+        return suggestions;
+    }
+
+    // Check a mnemonic, returning why it is invalid if it isn't.
+    // Only English mnemonics can be used to log in, so mnemonics in other
+    // languages are reported as such rather than as having unknown words
+    nlohmann::json check_mnemonic(const std::string& mnemonic)
+    {
+        std::vector<std::string> words;
+        const auto trimmed = boost::algorithm::to_lower_copy(boost::algorithm::trim_copy(mnemonic));
+        if (!trimmed.empty()) {
+            boost::algorithm::split(words, trimmed, boost::algorithm::is_space(), boost::algorithm::token_compress_on);
+        }
+        nlohmann::json result = { { "valid", false }, { "error", std::string() }, { "word_count", words.size() },
+            { "unknown_words", nlohmann::json::array() } };
+
+        const auto english = get_bip39_wordlist(nullptr);
+        const auto is_known = [](const std::vector<std::string>& wordlist, const std::string& word) {
+            return std::find(wordlist.begin(), wordlist.end(), word) != wordlist.end();
+        };
+        for (size_t i = 0; i < words.size(); ++i) {
+            if (!is_known(english, words[i])) {
+                result["unknown_words"].push_back({ { "index", i }, { "word", words[i] },
+                    { "suggestions", get_word_suggestions(english, words[i]) } });
+            }
+        }
+
+        if (words.size() < 12 || words.size() > 24 || words.size() % 3 != 0) {
+            result["error"] = "invalid_word_count";
+        } else if (!result["unknown_words"].empty()) {
+            result["error"] = "unknown_words";
+            char* languages;
+            GDK_VERIFY(bip39_get_languages(&languages));
+            std::vector<std::string> langs;
+            boost::algorithm::split(langs, languages, boost::algorithm::is_space());
+            wally_free_string(languages);
+            for (const auto& lang : langs) {
+                const auto wordlist = get_bip39_wordlist(lang.c_str());
+                if (std::all_of(words.begin(), words.end(),
+                        [&](const std::string& word) { return is_known(wordlist, word); })) {
+                    result["error"] = "unsupported_language";
+                    result["language"] = lang;
+                    break;
+                }
+            }
+        } else if (::bip39_mnemonic_validate(nullptr, boost::algorithm::join(words, " ").c_str()) != WALLY_OK) {
+            result["error"] = "invalid_checksum";
+        } else {
+            result["valid"] = true;
+            result["language"] = "en";
+        }
+        return result;
+    }
+
     // Parse a bitcoin uri as described in bip21/72 and return the components
     // If the uri passed is not a bitcoin uri return a null json object.
+    // Decode %XX escapes in a URI component. Invalid escapes are left as is
+    static std::string url_decode(const std::string& input)
+    {
+        const auto hex_value = [](char c) {
+            if (c >= '0' && c <= '9') {
+                return c - '0';
+            }
+            c = static_cast<char>(tolower(c));
+            return c >= 'a' && c <= 'f' ? c - 'a' + 10 : -1;
+        };
+
+        std::string decoded;
+        decoded.reserve(input.size());
+        for (size_t i = 0; i < input.size(); ++i) {
+            if (input[i] == '%' && i + 2 < input.size() && hex_value(input[i + 1]) >= 0
+                && hex_value(input[i + 2]) >= 0) {
+                decoded.push_back(static_cast<char>(hex_value(input[i + 1]) * 16 + hex_value(input[i + 2])));
+                i += 2;
+            } else {
+                decoded.push_back(input[i]);
+            }
+        }
+        return decoded;
+    }
+
     nlohmann::json parse_bitcoin_uri(const std::string& uri, const std::string& expected_scheme)
     {
         // Split a string into a head and tail around the first (leftmost) occurrence
@@ -247,8 +378,6 @@ namespace sdk {
         };
 
         // TODO: Take either the label or message and set the tx memo field with it if not set
-        // FIXME: URL unescape the arguments before returning
-        //
         std::string uri_copy = uri;
         boost::trim(uri_copy);
         nlohmann::json parsed;
@@ -272,7 +401,7 @@ namespace sdk {
                 if (boost::algorithm::starts_with(key, "req-")) {
                     throw user_error(res::id_unknown_bip21_parameter);
                 }
-                params.emplace(key, value);
+                params.emplace(url_decode(key), url_decode(value));
             }
             parsed["bip21-params"] = params;
 
@@ -286,6 +415,18 @@ namespace sdk {
         return parsed;
     }
 
+    // Make a bip21 style uri from an address and an object of string parameters
+    std::string make_bitcoin_uri(const std::string& scheme, const std::string& address, const nlohmann::json& params)
+    {
+        std::string uri = scheme + ":" + address;
+        char separator = '?';
+        for (const auto& param : params.items()) {
+            uri += separator + param.key() + "=" + param.value().get<std::string>();
+            separator = '&';
+        }
+        return uri;
+    }
+
     // Lookup key in json and if present decode it as hex and return the bytes, if not present
     // return the result of calling f()
     // This is useful in a couple of places where a bytes value can be optionally overridden in json
@@ -360,11 +501,12 @@ extern "C" int GA_get_random_bytes(size_t num_bytes, unsigned char* output_bytes
     }
 }
 
-extern "C" int GA_generate_mnemonic(char** output)
+namespace {
+template <std::size_t N> int generate_mnemonic(char** output)
 {
     try {
         GDK_RUNTIME_ASSERT(output);
-        auto entropy = ga::sdk::get_random_bytes<32>();
+        auto entropy = ga::sdk::get_random_bytes<N>();
         GDK_VERIFY(::bip39_mnemonic_from_bytes(nullptr, entropy.data(), entropy.size(), output));
         wally_bzero(entropy.data(), entropy.size());
         return GA_OK;
@@ -372,6 +514,11 @@ extern "C" int GA_generate_mnemonic(char** output)
         return GA_ERROR;
     }
 }
+} // namespace
+
+extern "C" int GA_generate_mnemonic(char** output) { return generate_mnemonic<32>(output); }
+
+extern "C" int GA_generate_mnemonic_12(char** output) { return generate_mnemonic<16>(output); }
 
 extern "C" int GA_validate_mnemonic(const char* mnemonic, uint32_t* valid)
 {
@@ -67,12 +67,14 @@ namespace sdk {
     }
 
     nlohmann::json parse_bitcoin_uri(const std::string& uri, const std::string& expected_scheme);
+    std::string make_bitcoin_uri(const std::string& scheme, const std::string& address, const nlohmann::json& params);
     // TODO: URI parsing
     std::pair<std::string, std::string> split_url(const std::string& domain_name, std::string& target, bool& secure);
 
     // Mnemonic handling
     std::string encrypt_mnemonic(const std::string& plaintext_mnemonic, const std::string& password);
     std::string decrypt_mnemonic(const std::string& encrypted_mnemonic, const std::string& password);
+    nlohmann::json check_mnemonic(const std::string& mnemonic);
 
     // Encryption
     std::string aes_cbc_decrypt(
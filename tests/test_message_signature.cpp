@@ -0,0 +1,58 @@
+#include "src/ga_wally.hpp"
+#include "src/memory.hpp"
+#include "src/network_parameters.hpp"
+#include "src/session.hpp"
+#include "src/transaction_utils.hpp"
+#include "src/utils.hpp"
+#include <assert.h>
+#include <nlohmann/json.hpp>
+#include <stdio.h>
+#include <stdlib.h>
+
+using namespace ga::sdk;
+
+static const std::string private_key_hex("5ef8339db9e4443403b2032035157c29df73b2b2919782b2d16bb444edcb15c7");
+static const std::string public_key_hex("032c97a464d749a0ba968efa8487569c3058b93bd11e6fe2eca754fb13181b4edc");
+static const std::string message("I own this address");
+// A BIP137 signature of message by the key above, for its p2pkh address
+static const std::string signature(
+    "H4eaNqL+jE9/2x5hocBhwvwK4vI48gNOC5fNeBgUBv0HJX6SlfCFdIBBjEtCjdgjxyFefBqzuVHbTTRWXG1UJPo=");
+
+static std::vector<unsigned char> recover(const std::string& msg, const std::string& sig)
+{
+    return get_message_signature_public_key(format_bitcoin_message_hash(ustring_span(msg)), sig);
+}
+
+int main()
+{
+    nlohmann::json init_config;
+    init_config["datadir"] = ".";
+    ga::sdk::init(init_config);
+
+    const network_parameters net_params(network_parameters::get("mainnet"));
+    const auto public_key = h2b(public_key_hex);
+
+    // The key recovered from a known signature pays to its p2pkh and p2sh-p2wpkh addresses only
+    assert(recover(message, signature) == public_key);
+    assert(is_public_key_address(net_params, "1C7dkfC3jhzgSMA6ffoUFRybbwG8uAG1DZ", public_key));
+    assert(is_public_key_address(net_params, "33REUWBmUf72dSShZJryzxRE3Hg1dggHJK", public_key));
+    assert(!is_public_key_address(net_params, "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH", public_key));
+    assert(!is_public_key_address(net_params, "not an address", public_key));
+
+    // A different message recovers a different key
+    assert(recover(message + ".", signature) != public_key);
+
+    // Malformed signatures are rejected
+    bool thrown = false;
+    try {
+        recover(message, signature.substr(4));
+    } catch (const std::exception&) {
+        thrown = true;
+    }
+    assert(thrown);
+
+    // Signatures we encode recover the signing key
+    const auto hash = format_bitcoin_message_hash(ustring_span(message));
+    const auto sig = ec_sig_from_bytes(h2b(private_key_hex), hash);
+    assert(recover(message, get_message_signature(hash, public_key, sig)) == public_key);
+}
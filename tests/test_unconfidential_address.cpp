@@ -0,0 +1,41 @@
+#include "src/ga_strings.hpp"
+#include "src/ga_wally.hpp"
+#include "src/network_parameters.hpp"
+#include "src/session.hpp"
+#include "src/transaction_utils.hpp"
+#include <assert.h>
+#include <nlohmann/json.hpp>
+#include <stdio.h>
+#include <stdlib.h>
+
+using namespace ga::sdk;
+
+static nlohmann::json add_output(const network_parameters& net_params, const std::string& address, size_t& script_len)
+{
+    nlohmann::json result = { { "allow_unconfidential_addressees", true } };
+    auto tx = tx_init(0, 0);
+    add_tx_output(net_params, result, tx, address, 1000, std::string());
+    assert(tx->num_outputs == 1);
+    script_len = tx->outputs[0].script_len;
+    return result;
+}
+
+int main()
+{
+    nlohmann::json init_config;
+    init_config["datadir"] = ".";
+    ga::sdk::init(init_config);
+
+    const network_parameters net_params(network_parameters::get("liquid"));
+    size_t script_len;
+
+    // An unconfidential Liquid p2sh address is accepted when allowed
+    auto result = add_output(net_params, "GsrnxPWr46vw3DdNB96kjk2ffnVLjqHJLc", script_len);
+    assert(result.value("error", std::string()).empty());
+    assert(script_len != 0);
+
+    // A bitcoin mainnet address must not be paid to an empty script (i.e. as fee)
+    result = add_output(net_params, "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH", script_len);
+    assert(result.value("error", std::string()) == res::id_invalid_address);
+    assert(script_len != 0);
+}